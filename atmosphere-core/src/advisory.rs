@@ -0,0 +1,303 @@
+//! Postgres Advisory Locks
+//!
+//! Advisory locks serialize work on a per-entity basis (e.g. one job per user at a time) without
+//! locking any rows. The lock keys are derived from the schema and table name of `T` together
+//! with the primary key of the entity, so that entities of different tables never share a lock.
+//!
+//! Locks come in two scopes:
+//!
+//! - Session scoped locks are held by an [`AdvisoryLock`] guard until it is released (or dropped).
+//! - Transaction scoped locks are acquired using [`lock_xact`] / [`try_lock_xact`] and are
+//!   released by the database once the enclosing transaction ends.
+//!
+//! ```ignore
+//! use atmosphere::advisory::AdvisoryLock;
+//!
+//! let lock = AdvisoryLock::acquire::<User>(&user.id, &pool).await?;
+//! // .. process the jobs of `user`
+//! lock.release().await?;
+//! ```
+
+use std::hash::Hasher;
+use std::ops::{Deref, DerefMut};
+
+use sqlx::pool::PoolConnection;
+use sqlx::{Executor, PgConnection, Postgres};
+
+use crate::query::{Operation, QueryError};
+use crate::{Error, Result, Table};
+
+/// FNV-1a, which (unlike the std hashers) produces the same hashes across processes and releases.
+/// Together with the canonical encoding of [`LockKey`], this lets different instances of an
+/// application agree on the keys of locks.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Primary keys which can be used to derive advisory lock keys.
+///
+/// Unlike `std::hash::Hash`, whose output depends on the platform (e.g. the byte order of
+/// integers) and the standard library, the bytes written by `LockKey` are canonical.
+pub trait LockKey {
+    /// Writes the canonical encoding of the key to `hasher`.
+    fn write_key(&self, hasher: &mut impl Hasher);
+}
+
+macro_rules! lock_key {
+    ($($ty:ty),+) => {
+        $(
+            impl LockKey for $ty {
+                fn write_key(&self, hasher: &mut impl Hasher) {
+                    hasher.write(&self.to_be_bytes());
+                }
+            }
+        )+
+    };
+}
+
+lock_key!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl LockKey for str {
+    fn write_key(&self, hasher: &mut impl Hasher) {
+        write_bytes(hasher, self.as_bytes());
+    }
+}
+
+impl LockKey for String {
+    fn write_key(&self, hasher: &mut impl Hasher) {
+        self.as_str().write_key(hasher);
+    }
+}
+
+impl LockKey for Vec<u8> {
+    fn write_key(&self, hasher: &mut impl Hasher) {
+        write_bytes(hasher, self);
+    }
+}
+
+/// Writes `bytes` prefixed by their length, so that consecutive values can not run into each
+/// other (e.g. `("ab", "c")` and `("a", "bc")`).
+fn write_bytes(hasher: &mut impl Hasher, bytes: &[u8]) {
+    hasher.write(&(bytes.len() as u64).to_be_bytes());
+    hasher.write(bytes);
+}
+
+/// Computes the advisory lock key for the entity of table `T` with the primary key `pk`.
+pub fn key<T: Table>(pk: &T::PrimaryKey) -> i64
+where
+    T::PrimaryKey: LockKey,
+{
+    let mut hasher = Fnv::default();
+
+    write_bytes(&mut hasher, T::SCHEMA.as_bytes());
+    write_bytes(&mut hasher, T::TABLE.as_bytes());
+    pk.write_key(&mut hasher);
+
+    hasher.finish() as i64
+}
+
+fn error<T: Table>(err: sqlx::Error) -> Error {
+    Error::Query(QueryError::from(err).within::<T>(Operation::Other))
+}
+
+/// A session scoped advisory lock on an entity.
+///
+/// The guard holds the connection the lock was acquired on, which can be used through `Deref`.
+/// If the guard is dropped without calling [`AdvisoryLock::release`], its connection is closed
+/// instead of being returned to the pool, which releases the lock on the server.
+#[derive(Debug)]
+pub struct AdvisoryLock {
+    key: i64,
+    conn: Option<PoolConnection<Postgres>>,
+    error: fn(sqlx::Error) -> Error,
+}
+
+impl AdvisoryLock {
+    /// Acquires the lock of the entity of table `T` with the primary key `pk`, waiting until it
+    /// is released by concurrent holders.
+    pub async fn acquire<T: Table>(pk: &T::PrimaryKey, pool: &crate::Pool) -> Result<Self>
+    where
+        T::PrimaryKey: LockKey,
+    {
+        let key = key::<T>(pk);
+
+        let mut conn = pool.acquire().await.map_err(error::<T>)?;
+
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(key)
+            .execute(&mut *conn)
+            .await
+            .map_err(error::<T>)?;
+
+        Ok(Self {
+            key,
+            conn: Some(conn),
+            error: error::<T>,
+        })
+    }
+
+    /// Acquires the lock of the entity of table `T` with the primary key `pk` if it is not held
+    /// by a concurrent holder, returning `None` otherwise.
+    pub async fn try_acquire<T: Table>(
+        pk: &T::PrimaryKey,
+        pool: &crate::Pool,
+    ) -> Result<Option<Self>>
+    where
+        T::PrimaryKey: LockKey,
+    {
+        let key = key::<T>(pk);
+
+        let mut conn = pool.acquire().await.map_err(error::<T>)?;
+
+        let (locked,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(error::<T>)?;
+
+        Ok(locked.then_some(Self {
+            key,
+            conn: Some(conn),
+            error: error::<T>,
+        }))
+    }
+
+    /// The key of this lock
+    pub const fn key(&self) -> i64 {
+        self.key
+    }
+
+    /// Releases the lock and returns its connection to the pool. If the lock can not be released,
+    /// the connection is closed instead, which releases the lock on the server.
+    pub async fn release(mut self) -> Result<()> {
+        let mut conn = self.conn.take().expect("lock was already released");
+
+        let res = sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(self.key)
+            .execute(&mut *conn)
+            .await;
+
+        if let Err(err) = res {
+            drop(conn.detach());
+            return Err((self.error)(err));
+        }
+
+        Ok(())
+    }
+}
+
+impl Deref for AdvisoryLock {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().expect("lock was already released")
+    }
+}
+
+impl DerefMut for AdvisoryLock {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().expect("lock was already released")
+    }
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // closing the session releases all of its advisory locks
+            drop(conn.detach());
+        }
+    }
+}
+
+/// Acquires the transaction scoped lock of the entity of table `T` with the primary key `pk`,
+/// waiting until it is released by concurrent holders. The lock is released once the enclosing
+/// transaction ends.
+pub async fn lock_xact<'e, T, E>(pk: &T::PrimaryKey, executor: E) -> Result<()>
+where
+    T: Table,
+    T::PrimaryKey: LockKey,
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(key::<T>(pk))
+        .execute(executor)
+        .await
+        .map_err(error::<T>)?;
+
+    Ok(())
+}
+
+/// Acquires the transaction scoped lock of the entity of table `T` with the primary key `pk` if
+/// it is not held by a concurrent holder. Returns whether the lock was acquired.
+pub async fn try_lock_xact<'e, T, E>(pk: &T::PrimaryKey, executor: E) -> Result<bool>
+where
+    T: Table,
+    T::PrimaryKey: LockKey,
+    E: Executor<'e, Database = Postgres>,
+{
+    let (locked,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_xact_lock($1)")
+        .bind(key::<T>(pk))
+        .fetch_one(executor)
+        .await
+        .map_err(error::<T>)?;
+
+    Ok(locked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_bytes, Fnv, LockKey};
+    use std::hash::Hasher;
+
+    #[test]
+    fn fnv() {
+        let mut hasher = Fnv::default();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let mut hasher = Fnv::default();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn canonical() {
+        let mut hasher = Fnv::default();
+        1i32.write_key(&mut hasher);
+
+        let mut expected = Fnv::default();
+        expected.write(&[0, 0, 0, 1]);
+
+        assert_eq!(hasher.finish(), expected.finish());
+
+        // length prefixes separate consecutive values
+        let mut a = Fnv::default();
+        write_bytes(&mut a, b"ab");
+        write_bytes(&mut a, b"c");
+
+        let mut b = Fnv::default();
+        write_bytes(&mut b, b"a");
+        write_bytes(&mut b, b"bc");
+
+        assert_ne!(a.finish(), b.finish());
+    }
+}
@@ -38,6 +38,17 @@ pub enum BindError {
     #[error("unknown column: {0}")]
     #[diagnostic(code(atmosphere::bind::unknown))]
     Unknown(&'static str),
+
+    /// Represents an error where an identifier interpolated into raw sql is unknown or
+    /// unterminated.
+    #[error("invalid identifier: {0}")]
+    #[diagnostic(code(atmosphere::bind::identifier))]
+    Identifier(String),
+
+    /// Represents an error where a value of the given sql type can not be encoded, e.g. as CSV.
+    #[error("unsupported type: {0}")]
+    #[diagnostic(code(atmosphere::bind::unsupported))]
+    Unsupported(String),
 }
 
 type Query<'q, DB> = sqlx::query::Query<'q, DB, <DB as HasArguments<'q>>::Arguments>;
@@ -94,4 +105,56 @@ impl<'q> Bindable<'q> for QueryBuilder<'q, crate::Driver> {
 pub trait Bind: Table {
     /// Binds a single column of the implementing table entity to a given query.
     fn bind<'q, Q: Bindable<'q>>(&'q self, c: &'q Column<Self>, query: Q) -> Result<Q>;
+
+    /// Whether the value of a single column differs between `self` and `snapshot`. Columns whose
+    /// values cannot be compared are always considered changed.
+    fn changed(&self, c: &Column<Self>, snapshot: &Self) -> bool {
+        let _ = (c, snapshot);
+        true
+    }
+}
+
+/// Compares column values if their type implements `PartialEq` and falls back to considering
+/// them changed otherwise, using autoref-based specialization: the method of `Compare` is
+/// preferred during method resolution, since it does not require an additional autoref.
+#[doc(hidden)]
+pub mod change {
+    pub struct Probe<'a, T>(pub &'a T, pub &'a T);
+
+    pub trait Compare {
+        fn changed(&self) -> bool;
+    }
+
+    impl<T: PartialEq> Compare for Probe<'_, T> {
+        fn changed(&self) -> bool {
+            self.0 != self.1
+        }
+    }
+
+    pub trait Fallback {
+        fn changed(&self) -> bool;
+    }
+
+    impl<T> Fallback for &Probe<'_, T> {
+        fn changed(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::change::{Compare as _, Fallback as _, Probe};
+
+    struct Opaque;
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn change() {
+        assert!(!(&Probe(&1, &1)).changed());
+        assert!((&Probe(&1, &2)).changed());
+
+        // values without `PartialEq` are always considered changed
+        assert!((&Probe(&Opaque, &Opaque)).changed());
+    }
 }
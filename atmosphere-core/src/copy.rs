@@ -0,0 +1,339 @@
+//! Postgres `COPY FROM STDIN`
+//!
+//! `COPY` is the fastest way to import large amounts of rows into postgres, being considerably
+//! faster than (multi-row) `INSERT` statements. This module streams rows into a table using the
+//! column order of `Table::PRIMARY_KEY`, `Table::FOREIGN_KEYS`, `Table::DATA_COLUMNS`, the version
+//! column and `Table::TIMESTAMP_COLUMNS` – which is also the column order of
+//! [`sql::insert`](crate::runtime::sql::insert).
+//!
+//! Entities are encoded using their `sqlx::Encode` implementations, either in the binary format
+//! or as CSV, while pre-formatted CSV data (e.g. from files) can be imported as is. Hooks are not
+//! run for copied rows.
+//!
+//! ```ignore
+//! use atmosphere::copy::{self, CopyFormat};
+//!
+//! let rows = copy::copy_in(&users, CopyFormat::Binary, &pool).await?;
+//! let rows = copy::copy_in_csv::<User, _, _>(["1,jane\n2,john\n"], &pool).await?;
+//! ```
+
+use sqlx::encode::IsNull;
+use sqlx::postgres::PgArgumentBuffer;
+use sqlx::types::chrono::{DateTime, NaiveDate, NaiveTime};
+use sqlx::{Acquire, Encode, Postgres, Type, TypeInfo};
+
+use crate::bind::BindError;
+use crate::query::{Operation, QueryError};
+use crate::{Bind, Bindable, Column, Error, Result, Table};
+
+/// The binary format header: the signature, flags and the length of the header extension
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// The binary format trailer: a field count of `-1`
+const TRAILER: &[u8] = &(-1i16).to_be_bytes();
+
+/// Rows are sent to the database in chunks of (roughly) this size
+const CHUNK: usize = 64 * 1024;
+
+/// Describes the data format of a `COPY` statement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// The postgres binary format
+    Binary,
+    /// Comma separated values without a header line
+    Csv,
+}
+
+/// Generates the `COPY FROM STDIN` statement for table `T` in the given format.
+///
+/// SQL: `COPY .. (..) FROM STDIN WITH (FORMAT ..)`
+pub fn statement<T: Bind>(format: CopyFormat) -> String {
+    let query = crate::runtime::sql::insert::<T>();
+
+    let columns: Vec<&str> = query.bindings().columns().iter().map(|c| c.sql()).collect();
+
+    let format = match format {
+        CopyFormat::Binary => "binary",
+        CopyFormat::Csv => "csv",
+    };
+
+    format!(
+        "COPY \"{}\".\"{}\" ({}) FROM STDIN WITH (FORMAT {format})",
+        T::SCHEMA,
+        T::TABLE,
+        columns.join(", ")
+    )
+}
+
+/// A tuple in the binary format, encoded through `Bind`.
+struct Tuple(Vec<u8>);
+
+impl<'q> Bindable<'q> for Tuple {
+    fn dyn_bind<V: 'q + Send + Encode<'q, Postgres> + Type<Postgres>>(mut self, value: V) -> Self {
+        let mut buf = PgArgumentBuffer::default();
+
+        match value.encode_by_ref(&mut buf) {
+            IsNull::Yes => self.0.extend((-1i32).to_be_bytes()),
+            IsNull::No => {
+                self.0.extend((buf.len() as i32).to_be_bytes());
+                self.0.extend(buf.iter());
+            }
+        }
+
+        self
+    }
+}
+
+/// A line of CSV, encoded through `Bind`. As `sqlx` only encodes values in the binary format,
+/// the binary values of the builtin scalar types are converted to their text format.
+struct Line {
+    buf: Vec<u8>,
+    fields: usize,
+    unsupported: Option<String>,
+}
+
+impl<'q> Bindable<'q> for Line {
+    fn dyn_bind<V: 'q + Send + Encode<'q, Postgres> + Type<Postgres>>(mut self, value: V) -> Self {
+        if self.fields > 0 {
+            self.buf.push(b',');
+        }
+
+        self.fields += 1;
+
+        let mut buf = PgArgumentBuffer::default();
+
+        // null values are unquoted empty fields
+        if let IsNull::Yes = value.encode_by_ref(&mut buf) {
+            return self;
+        }
+
+        let ty = value.produces().unwrap_or_else(V::type_info);
+
+        match text(ty.name(), &buf) {
+            Some(text) => {
+                self.buf.push(b'"');
+                self.buf.extend(text.replace('"', "\"\"").as_bytes());
+                self.buf.push(b'"');
+            }
+            None => self.unsupported = Some(ty.name().to_owned()),
+        }
+
+        self
+    }
+}
+
+/// Converts the binary value `bytes` of the sql type `ty` to its text format, or returns `None`
+/// for unsupported types.
+fn text(ty: &str, bytes: &[u8]) -> Option<String> {
+    Some(match ty {
+        "BOOL" => (bytes.first()? != &0).to_string(),
+        // `"char"` keeps only the first byte of its text input, so the byte itself is written.
+        // non-ascii bytes are not valid utf-8 on their own and are rejected
+        "\"CHAR\"" => match bytes {
+            [0] => String::new(),
+            [b] if b.is_ascii() => char::from(*b).to_string(),
+            _ => return None,
+        },
+        "INT2" => i16::from_be_bytes(bytes.try_into().ok()?).to_string(),
+        "INT4" => i32::from_be_bytes(bytes.try_into().ok()?).to_string(),
+        "INT8" => i64::from_be_bytes(bytes.try_into().ok()?).to_string(),
+        "FLOAT4" => float(f32::from_be_bytes(bytes.try_into().ok()?).into()),
+        "FLOAT8" => float(f64::from_be_bytes(bytes.try_into().ok()?)),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "JSON" => std::str::from_utf8(bytes).ok()?.into(),
+        // binary jsonb is prefixed by its format version
+        "JSONB" => std::str::from_utf8(bytes.get(1..)?).ok()?.into(),
+        "BYTEA" => bytes
+            .iter()
+            .fold("\\x".to_owned(), |hex, b| hex + &format!("{b:02x}")),
+        // dates and timestamps are relative to 2000-01-01
+        "DATE" => {
+            let days = i32::from_be_bytes(bytes.try_into().ok()?);
+            NaiveDate::from_num_days_from_ce_opt(days.checked_add(730_120)?)?.to_string()
+        }
+        "TIME" => {
+            let micros = i64::from_be_bytes(bytes.try_into().ok()?);
+            let secs = u32::try_from(micros / 1_000_000).ok()?;
+            let nanos = (micros % 1_000_000 * 1_000) as u32;
+            NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)?.to_string()
+        }
+        "TIMESTAMP" | "TIMESTAMPTZ" => {
+            let micros = i64::from_be_bytes(bytes.try_into().ok()?);
+            let timestamp =
+                DateTime::from_timestamp_micros(micros.checked_add(946_684_800_000_000)?)?
+                    .naive_utc();
+
+            match ty {
+                "TIMESTAMPTZ" => format!("{timestamp}+00"),
+                _ => timestamp.to_string(),
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Formats `f` like postgres, which spells infinite values out.
+fn float(f: f64) -> String {
+    match f {
+        f if f == f64::INFINITY => "Infinity".to_owned(),
+        f if f == f64::NEG_INFINITY => "-Infinity".to_owned(),
+        f => f.to_string(),
+    }
+}
+
+/// Encodes `entity` in `format` and appends it to `buf`.
+fn encode<T: Bind>(
+    entity: &T,
+    columns: &[Column<T>],
+    format: CopyFormat,
+    mut buf: Vec<u8>,
+) -> Result<Vec<u8>> {
+    match format {
+        CopyFormat::Binary => {
+            buf.extend((columns.len() as i16).to_be_bytes());
+
+            let mut tuple = Tuple(buf);
+
+            for c in columns {
+                tuple = entity.bind(c, tuple)?;
+            }
+
+            Ok(tuple.0)
+        }
+        CopyFormat::Csv => {
+            let mut line = Line {
+                buf,
+                fields: 0,
+                unsupported: None,
+            };
+
+            for c in columns {
+                line = entity.bind(c, line)?;
+
+                if let Some(ty) = line.unsupported {
+                    return Err(Error::Bind(BindError::Unsupported(ty)));
+                }
+            }
+
+            line.buf.push(b'\n');
+
+            Ok(line.buf)
+        }
+    }
+}
+
+fn error<T: Table>(err: sqlx::Error) -> Error {
+    Error::Query(QueryError::from(err).within::<T>(Operation::Insert))
+}
+
+/// Copies `entities` into their table using `format` and returns the number of copied rows. If
+/// any row fails, the whole `COPY` fails and no row is copied.
+///
+/// In the binary format, columns of composite types or arrays of custom types are not supported,
+/// as their type ids are only resolved when binding them to regular queries. CSV supports columns
+/// of the builtin scalar types (booleans, integers, floats, text, json, `bytea`, dates, times and
+/// timestamps) and fails with `BindError::Unsupported` for all other types.
+pub async fn copy_in<'a, 'e, T, A>(
+    entities: impl IntoIterator<Item = &'e T>,
+    format: CopyFormat,
+    conn: A,
+) -> Result<u64>
+where
+    T: Bind + 'e,
+    A: Acquire<'a, Database = Postgres>,
+{
+    let query = crate::runtime::sql::insert::<T>();
+    let columns = query.bindings().columns();
+
+    let mut conn = conn.acquire().await.map_err(error::<T>)?;
+
+    let mut copy = conn
+        .copy_in_raw(&statement::<T>(format))
+        .await
+        .map_err(error::<T>)?;
+
+    let mut buf = match format {
+        CopyFormat::Binary => HEADER.to_vec(),
+        CopyFormat::Csv => vec![],
+    };
+
+    for entity in entities {
+        buf = match encode(entity, columns, format, buf) {
+            Ok(buf) => buf,
+            Err(err) => {
+                // the bind error is more meaningful than a failed abort
+                let _ = copy.abort("failed to encode row").await;
+                return Err(err);
+            }
+        };
+
+        if buf.len() >= CHUNK {
+            copy.send(buf.as_slice()).await.map_err(error::<T>)?;
+            buf.clear();
+        }
+    }
+
+    if format == CopyFormat::Binary {
+        buf.extend(TRAILER);
+    }
+
+    copy.send(buf).await.map_err(error::<T>)?;
+    copy.finish().await.map_err(error::<T>)
+}
+
+/// Copies pre-formatted CSV data into table `T` and returns the number of copied rows. This is the
+/// lower-level counterpart of [`copy_in`] for data which is already encoded, e.g. read from files.
+/// The data must not contain a header line and its columns must be in the column order of `T`
+/// (see [`statement`]). The chunks are sent as is and are not required to align with lines.
+pub async fn copy_in_csv<'a, T, A, B>(chunks: impl IntoIterator<Item = B>, conn: A) -> Result<u64>
+where
+    T: Bind,
+    A: Acquire<'a, Database = Postgres>,
+    B: AsRef<[u8]>,
+{
+    let mut conn = conn.acquire().await.map_err(error::<T>)?;
+
+    let mut copy = conn
+        .copy_in_raw(&statement::<T>(CopyFormat::Csv))
+        .await
+        .map_err(error::<T>)?;
+
+    for chunk in chunks {
+        copy.send(chunk.as_ref()).await.map_err(error::<T>)?;
+    }
+
+    copy.finish().await.map_err(error::<T>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{text, HEADER, TRAILER};
+
+    #[test]
+    fn binary_format() {
+        assert_eq!(HEADER.len(), 19);
+        assert_eq!(&HEADER[..11], b"PGCOPY\n\xff\r\n\0");
+        assert_eq!(TRAILER, &[0xff, 0xff]);
+    }
+
+    #[test]
+    fn csv_text() {
+        assert_eq!(text("BOOL", &[1]).unwrap(), "true");
+        assert_eq!(text("\"CHAR\"", b"A").unwrap(), "A");
+        assert_eq!(text("\"CHAR\"", &[0]).unwrap(), "");
+        assert!(text("\"CHAR\"", &[0xe9]).is_none());
+        assert_eq!(text("INT4", &(-7i32).to_be_bytes()).unwrap(), "-7");
+        assert_eq!(
+            text("FLOAT8", &f64::INFINITY.to_be_bytes()).unwrap(),
+            "Infinity"
+        );
+        assert_eq!(text("BYTEA", &[0xde, 0xad]).unwrap(), "\\xdead");
+        assert_eq!(text("DATE", &1i32.to_be_bytes()).unwrap(), "2000-01-02");
+        assert_eq!(
+            text("TIMESTAMPTZ", &1_500_000i64.to_be_bytes()).unwrap(),
+            "2000-01-01 00:00:01.500+00"
+        );
+        assert_eq!(text("JSONB", b"\x01{}").unwrap(), "{}");
+        assert!(text("INT4", &[0]).is_none());
+        assert!(text("POINT", &[]).is_none());
+    }
+}
@@ -34,6 +34,17 @@ pub enum Error {
     #[diagnostic(transparent)]
     Bind(#[from] BindError),
 
+    /// The entity was modified concurrently, its version column did not match the stored row
+    #[error("stale entity")]
+    #[diagnostic(code(atmosphere::stale_entity))]
+    StaleEntity,
+
+    /// A statement affecting many rows was refused, as its filter has no conditions. Use
+    /// `Filter::all` to explicitly affect all rows.
+    #[error("empty filter")]
+    #[diagnostic(code(atmosphere::empty_filter))]
+    EmptyFilter,
+
     #[error("other")]
     #[diagnostic(code(atmosphere::other))]
     Other,
@@ -43,6 +54,17 @@ pub enum Error {
     Internal,
 }
 
+impl Error {
+    /// Whether the operation failed due to a transient conflict with concurrent transactions
+    /// (serialization failures, deadlocks and lock timeouts) and can be retried as a whole.
+    pub const fn is_retryable(&self) -> bool {
+        match self {
+            Self::Query(q) => q.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
 /// A specialized `Result` type for use throughout the Atmosphere framework.
 ///
 /// This type alias simplifies error handling by using the `Error` enum as the default error type.
@@ -0,0 +1,363 @@
+//! Row Filters
+//!
+//! Filters select the rows of statements which operate on many rows at once, such as
+//! [`Update::update_where`](crate::Update::update_where),
+//! [`Delete::delete_where`](crate::Delete::delete_where) and aggregates. Conditions refer to
+//! columns by their field names and are combined using `AND`, their values are bound as query
+//! parameters.
+//!
+//! To protect against accidentally affecting every row of a table, writing statements refuse
+//! filters without conditions unless they were explicitly created using [`Filter::all`].
+//!
+//! ```ignore
+//! use atmosphere::filter::{Assignments, Filter};
+//!
+//! let filter = Filter::<User>::new().eq("active", false).lt("last_login", cutoff);
+//! let archived = User::update_where(&filter, &Assignments::new().set("archived", true), &pool).await?;
+//! let deleted = User::delete_where(&Filter::all(), &pool).await?;
+//! ```
+
+use std::marker::PhantomData;
+
+use sqlx::database::HasArguments;
+use sqlx::{Arguments, Encode, Type};
+
+use crate::{bind::BindError, Column, Error, Result, Table};
+
+/// A value of a condition or assignment, bound as a query parameter.
+pub(crate) trait Value: Send + Sync {
+    fn add<'q>(&'q self, args: &mut <crate::Driver as HasArguments<'q>>::Arguments);
+}
+
+impl<V> Value for V
+where
+    V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+{
+    fn add<'q>(&'q self, args: &mut <crate::Driver as HasArguments<'q>>::Arguments) {
+        args.add(self);
+    }
+}
+
+enum Condition {
+    Compare(&'static str, &'static str, Box<dyn Value>),
+    Null(&'static str, bool),
+    In(&'static str, Vec<Box<dyn Value>>),
+    Any(Vec<Vec<Condition>>),
+}
+
+impl Condition {
+    fn sql<T: Table>(&self, next: &mut usize, qualifier: &str) -> Result<String> {
+        Ok(match self {
+            Self::Compare(field, op, _) => {
+                format!("{} {op} {}", column::<T>(field, qualifier)?, param(next))
+            }
+            Self::Null(field, true) => format!("{} IS NULL", column::<T>(field, qualifier)?),
+            Self::Null(field, false) => format!("{} IS NOT NULL", column::<T>(field, qualifier)?),
+            Self::In(field, values) if values.is_empty() => {
+                column::<T>(field, qualifier)?;
+                "1 = 0".to_string()
+            }
+            Self::In(field, values) => {
+                let column = column::<T>(field, qualifier)?;
+                let params: Vec<String> = values.iter().map(|_| param(next)).collect();
+                format!("{column} IN ({})", params.join(", "))
+            }
+            Self::Any(groups) => {
+                let mut alternatives = vec![];
+
+                for group in groups {
+                    alternatives.push(conjunction::<T>(group, next, qualifier)?);
+                }
+
+                format!("({})", alternatives.join(" OR "))
+            }
+        })
+    }
+
+    fn arguments<'q>(&'q self, args: &mut <crate::Driver as HasArguments<'q>>::Arguments) {
+        match self {
+            Self::Compare(_, _, value) => value.add(args),
+            Self::Null(..) => {}
+            Self::In(_, values) => values.iter().for_each(|v| v.add(args)),
+            Self::Any(groups) => groups.iter().flatten().for_each(|c| c.arguments(args)),
+        }
+    }
+}
+
+fn param(next: &mut usize) -> String {
+    *next += 1;
+    format!("${next}")
+}
+
+fn conjunction<T: Table>(
+    conditions: &[Condition],
+    next: &mut usize,
+    qualifier: &str,
+) -> Result<String> {
+    let mut sql = vec![];
+
+    for c in conditions {
+        sql.push(c.sql::<T>(next, qualifier)?);
+    }
+
+    Ok(sql.join(" AND "))
+}
+
+/// Resolves the sql name of the column of `field`, prefixed with `qualifier`.
+fn column<T: Table>(field: &'static str, qualifier: &str) -> Result<String> {
+    Column::<T>::by_field(field)
+        .map(|c| format!("{qualifier}{}", c.sql()))
+        .ok_or(Error::Bind(BindError::Unknown(field)))
+}
+
+/// Selects the rows of table `T` which match all of its conditions.
+pub struct Filter<T: Table> {
+    conditions: Vec<Condition>,
+    all: bool,
+    table: PhantomData<T>,
+}
+
+impl<T: Table> Default for Filter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Table> Filter<T> {
+    /// Creates a filter without conditions. Statements refuse it until a condition is added.
+    pub const fn new() -> Self {
+        Self {
+            conditions: vec![],
+            all: false,
+            table: PhantomData,
+        }
+    }
+
+    /// Creates a filter without conditions which explicitly selects all rows of the table.
+    pub const fn all() -> Self {
+        Self {
+            conditions: vec![],
+            all: true,
+            table: PhantomData,
+        }
+    }
+
+    fn compare<V>(mut self, field: &'static str, op: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.conditions
+            .push(Condition::Compare(field, op, Box::new(value)));
+        self
+    }
+
+    /// Matches rows whose `field` equals `value`.
+    pub fn eq<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, "=", value)
+    }
+
+    /// Matches rows whose `field` does not equal `value`.
+    pub fn ne<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, "<>", value)
+    }
+
+    /// Matches rows whose `field` is less than `value`.
+    pub fn lt<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, "<", value)
+    }
+
+    /// Matches rows whose `field` is less than or equal to `value`.
+    pub fn le<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, "<=", value)
+    }
+
+    /// Matches rows whose `field` is greater than `value`.
+    pub fn gt<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, ">", value)
+    }
+
+    /// Matches rows whose `field` is greater than or equal to `value`.
+    pub fn ge<V>(self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.compare(field, ">=", value)
+    }
+
+    /// Matches rows whose `field` is `NULL`.
+    pub fn is_null(mut self, field: &'static str) -> Self {
+        self.conditions.push(Condition::Null(field, true));
+        self
+    }
+
+    /// Matches rows whose `field` is not `NULL`.
+    pub fn is_not_null(mut self, field: &'static str) -> Self {
+        self.conditions.push(Condition::Null(field, false));
+        self
+    }
+
+    /// Matches rows whose `field` equals any of `values`. An empty list matches no row.
+    pub fn is_in<V>(mut self, field: &'static str, values: impl IntoIterator<Item = V>) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        let values = values
+            .into_iter()
+            .map(|v| Box::new(v) as Box<dyn Value>)
+            .collect();
+
+        self.conditions.push(Condition::In(field, values));
+        self
+    }
+
+    /// Matches rows which match either this filter or `other`. If either filter has no
+    /// conditions, the result has none either and is only accepted by statements if the filters
+    /// without conditions were created using [`Filter::all`].
+    pub fn or(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            let all = [&self, &other]
+                .iter()
+                .filter(|f| f.is_empty())
+                .all(|f| f.all);
+
+            return Self {
+                conditions: vec![],
+                all,
+                table: PhantomData,
+            };
+        }
+
+        Self {
+            conditions: vec![Condition::Any(vec![self.conditions, other.conditions])],
+            all: false,
+            table: PhantomData,
+        }
+    }
+
+    /// Whether the filter has no conditions
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Generates the `WHERE` clause of the filter, numbering its parameters after `next`.
+    /// Returns `None` for filters created using [`Filter::all`] without conditions and
+    /// `Error::EmptyFilter` for all other filters without conditions.
+    pub(crate) fn sql(&self, next: &mut usize) -> Result<Option<String>> {
+        if self.conditions.is_empty() {
+            return match self.all {
+                true => Ok(None),
+                false => Err(Error::EmptyFilter),
+            };
+        }
+
+        conjunction::<T>(&self.conditions, next, "").map(Some)
+    }
+
+    /// Generates the condition of the filter like `sql`, but returns `None` for all filters
+    /// without conditions, as reads of all rows are harmless.
+    pub(crate) fn condition(&self, next: &mut usize) -> Result<Option<String>> {
+        self.qualified_condition(next, "")
+    }
+
+    /// Like `condition`, but prefixes all columns with `qualifier` (e.g. `"t0".`), which is
+    /// required if the columns of several tables are in scope.
+    pub(crate) fn qualified_condition(
+        &self,
+        next: &mut usize,
+        qualifier: &str,
+    ) -> Result<Option<String>> {
+        if self.conditions.is_empty() {
+            return Ok(None);
+        }
+
+        conjunction::<T>(&self.conditions, next, qualifier).map(Some)
+    }
+
+    /// Adds the values of the filter to `args`, in the order of their parameters.
+    pub(crate) fn arguments<'q>(
+        &'q self,
+        args: &mut <crate::Driver as HasArguments<'q>>::Arguments,
+    ) {
+        for c in &self.conditions {
+            c.arguments(args);
+        }
+    }
+}
+
+/// The values assigned to columns by [`Update::update_where`](crate::Update::update_where).
+pub struct Assignments<T: Table> {
+    values: Vec<(&'static str, Box<dyn Value>)>,
+    table: PhantomData<T>,
+}
+
+impl<T: Table> Default for Assignments<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Table> Assignments<T> {
+    /// Creates empty assignments
+    pub const fn new() -> Self {
+        Self {
+            values: vec![],
+            table: PhantomData,
+        }
+    }
+
+    /// Assigns `value` to the column of `field`. Only foreign key, data and timestamp columns can
+    /// be assigned.
+    pub fn set<V>(mut self, field: &'static str, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.values.push((field, Box::new(value)));
+        self
+    }
+
+    /// Whether no column is assigned
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Generates the assignments of an `UPDATE`, numbering their parameters after `next`.
+    pub(crate) fn sql(&self, next: &mut usize) -> Result<Vec<String>> {
+        let mut sql = vec![];
+
+        for (field, _) in &self.values {
+            let column = match Column::<T>::by_field(field) {
+                Some(c @ (Column::ForeignKey(_) | Column::Data(_) | Column::Timestamp(_))) => c,
+                _ => return Err(Error::Bind(BindError::Unknown(field))),
+            };
+
+            sql.push(format!("{} = {}", column.sql(), param(next)));
+        }
+
+        Ok(sql)
+    }
+
+    /// Adds the assigned values to `args`, in the order of their parameters.
+    pub(crate) fn arguments<'q>(
+        &'q self,
+        args: &mut <crate::Driver as HasArguments<'q>>::Arguments,
+    ) {
+        for (_, value) in &self.values {
+            value.add(args);
+        }
+    }
+}
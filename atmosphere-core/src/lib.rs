@@ -21,13 +21,23 @@
 //! - Code reusability across API layers using generics.
 //! - Compile-time introspection for type-safe schema generation.
 
+#[cfg(feature = "postgres")]
+/// Serializes work on individual entities using postgres advisory locks keyed by table and
+/// primary key.
+pub mod advisory;
 #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
 /// Facilitates binding entities to queries, ensuring type safety and ease of use in query construction.
 pub mod bind;
+#[cfg(feature = "postgres")]
+/// Imports large amounts of rows using postgres `COPY FROM STDIN`.
+pub mod copy;
 #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
 /// Defines high-level database error types, offering a structured approach to error handling.
 pub mod error;
 #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+/// Filters the rows of statements operating on many rows at once.
+pub mod filter;
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
 /// Implements a hook system, allowing custom logic to be executed at different stages of database
 /// interactions.
 #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
@@ -52,9 +62,13 @@ pub mod schema;
 /// Provides utilities for automated testing of SQL interactions, ensuring reliability and
 /// correctness of database operations.
 pub mod testing;
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+/// Runs closures within database transactions, retrying them on transient failures such as
+/// serialization failures and deadlocks.
+pub mod transaction;
 
 #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
-pub use driver::{Driver, Pool};
+pub use driver::{ConnectOptions, Driver, Pool};
 
 /// Driver System
 ///
@@ -80,6 +94,10 @@ pub mod driver {
     /// Atmosphere Database Pool
     pub type Pool = sqlx::PgPool;
 
+    #[cfg(all(feature = "postgres", not(any(feature = "mysql", feature = "sqlite"))))]
+    /// Atmosphere Database Connect Options
+    pub type ConnectOptions = sqlx::postgres::PgConnectOptions;
+
     #[cfg(all(feature = "mysql", not(any(feature = "postgres", feature = "sqlite"))))]
     /// Atmosphere Database Driver
     pub type Driver = sqlx::MySql;
@@ -88,6 +106,10 @@ pub mod driver {
     /// Atmosphere Database Pool
     pub type Pool = sqlx::MySqlPool;
 
+    #[cfg(all(feature = "mysql", not(any(feature = "postgres", feature = "sqlite"))))]
+    /// Atmosphere Database Connect Options
+    pub type ConnectOptions = sqlx::mysql::MySqlConnectOptions;
+
     #[cfg(all(feature = "sqlite", not(any(feature = "postgres", feature = "mysql"))))]
     /// Atmosphere Database Driver
     pub type Driver = sqlx::Sqlite;
@@ -96,6 +118,26 @@ pub mod driver {
     /// Atmosphere Database Pool
     pub type Pool = sqlx::SqlitePool;
 
+    #[cfg(all(feature = "sqlite", not(any(feature = "postgres", feature = "mysql"))))]
+    /// Atmosphere Database Connect Options
+    pub type ConnectOptions = sqlx::sqlite::SqliteConnectOptions;
+
+    /// Disables the prepared statement cache of connections opened using `options`.
+    ///
+    /// Atmosphere prepares the statements it generates per table (e.g. `find`, `create` or
+    /// `delete`) once per connection and reuses them on later calls. Poolers which assign a
+    /// different server connection per transaction (e.g. pgbouncer in transaction pooling mode)
+    /// break prepared statements, so pools behind them have to opt out:
+    ///
+    /// ```ignore
+    /// let options = ConnectOptions::from_str(&url)?;
+    /// let pool = Pool::connect_with(atmosphere::driver::without_statement_cache(options)).await?;
+    /// ```
+    #[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+    pub fn without_statement_cache(options: ConnectOptions) -> ConnectOptions {
+        options.statement_cache_capacity(0)
+    }
+
     #[cfg(not(any(feature = "postgres", feature = "mysql", feature = "sqlite")))]
     compile_error!(
         "you must chose a atmosphere database driver (available: postgres, mysql, sqlite)"
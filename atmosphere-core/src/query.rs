@@ -5,7 +5,7 @@
 //! operations and cardinality, and a struct for building and managing queries for database tables.
 
 use miette::Diagnostic;
-use sqlx::QueryBuilder;
+use sqlx::{error::DatabaseError, QueryBuilder};
 use thiserror::Error;
 
 use crate::{runtime::sql::Bindings, Bind, Result, Table};
@@ -26,7 +26,7 @@ pub enum QueryError {
     /// Row not found errors
     #[error("not found")]
     #[diagnostic(code(atmosphere::query::not_found))]
-    NotFound(#[source] sqlx::Error),
+    NotFound(#[source] sqlx::Error, Box<ErrorContext>),
 
     /// SQLSTATE errors
     #[error("sql")]
@@ -60,23 +60,40 @@ pub enum ViolationError {
     /// Row uniqueness violated
     #[error("uniqueness violation")]
     #[diagnostic(code(atmosphere::violation::uniqueness))]
-    Unique(#[source] sqlx::Error),
+    Unique(#[source] sqlx::Error, Box<ErrorContext>),
 
     /// Foreign key violation
     #[error("foreign key violation")]
     #[diagnostic(code(atmosphere::violation::foreign_key))]
-    ForeignKey(#[source] sqlx::Error),
+    ForeignKey(#[source] sqlx::Error, Box<ErrorContext>),
 
     /// Integritry check failed
     #[error("integrity check")]
     #[diagnostic(code(atmosphere::violation::integrity))]
-    Check(#[source] sqlx::Error),
+    Check(#[source] sqlx::Error, Box<ErrorContext>),
+}
+
+impl ViolationError {
+    /// Access the context of the violated constraint
+    pub const fn context(&self) -> &ErrorContext {
+        match self {
+            Self::Unique(_, ctx) | Self::ForeignKey(_, ctx) | Self::Check(_, ctx) => ctx,
+        }
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        match self {
+            Self::Unique(_, ctx) | Self::ForeignKey(_, ctx) | Self::Check(_, ctx) => ctx,
+        }
+    }
 }
 
 /// Encapsulates errors derived from SQLSTATE codes.
 ///
 /// This enum categorizes various SQL errors such as data exceptions, integrity constraints, syntax
-/// errors, and others, based on their SQLSTATE classification.
+/// errors, and others, based on their SQLSTATE classification. Drivers which do not report
+/// SQLSTATE codes reliably (MySQL error numbers, SQLite extended result codes) are translated into
+/// the same taxonomy.
 #[derive(Debug, Diagnostic, Error)]
 #[non_exhaustive]
 pub enum SqlError {
@@ -95,6 +112,21 @@ pub enum SqlError {
     #[diagnostic(code(atmosphere::sqlstate::syntax))]
     Syntax(#[source] sqlx::Error),
 
+    /// SQLSTATE 40001: the transaction could not be serialized with concurrent transactions
+    #[error("serialization failure")]
+    #[diagnostic(code(atmosphere::sqlstate::serialization))]
+    SerializationFailure(#[source] sqlx::Error),
+
+    /// SQLSTATE 40P01: the transaction was aborted to resolve a deadlock
+    #[error("deadlock")]
+    #[diagnostic(code(atmosphere::sqlstate::deadlock))]
+    Deadlock(#[source] sqlx::Error),
+
+    /// SQLSTATE 55P03: a lock could not be acquired in time (or immediately, using `NOWAIT`)
+    #[error("lock timeout")]
+    #[diagnostic(code(atmosphere::sqlstate::lock_timeout))]
+    LockTimeout(#[source] sqlx::Error),
+
     /// All other classes
     #[error("other")]
     #[diagnostic(code(atmosphere::sqlstate::other))]
@@ -106,7 +138,7 @@ impl From<sqlx::Error> for QueryError {
         use sqlx::Error as E;
 
         match err {
-            E::RowNotFound => Self::NotFound(err),
+            E::RowNotFound => Self::NotFound(err, Box::default()),
             E::Io(_)
             | E::Protocol(_)
             | E::Tls(_)
@@ -116,40 +148,285 @@ impl From<sqlx::Error> for QueryError {
             | E::WorkerCrashed => Self::Io(err),
             E::Database(ref e) => {
                 if e.is_unique_violation() {
-                    return Self::Violation(ViolationError::Unique(err));
+                    let ctx = Box::new(ErrorContext::inspect(&**e));
+                    return Self::Violation(ViolationError::Unique(err, ctx));
                 }
 
                 if e.is_foreign_key_violation() {
-                    return Self::Violation(ViolationError::ForeignKey(err));
+                    let ctx = Box::new(ErrorContext::inspect(&**e));
+                    return Self::Violation(ViolationError::ForeignKey(err, ctx));
                 }
 
                 if e.is_check_violation() {
-                    return Self::Violation(ViolationError::Check(err));
+                    let ctx = Box::new(ErrorContext::inspect(&**e));
+                    return Self::Violation(ViolationError::Check(err, ctx));
+                }
+
+                if e.code().is_none() {
+                    return Self::Other(err);
+                }
+
+                match classify(&**e) {
+                    Some(sql) => Self::Sql(sql(err)),
+                    None => Self::InternalError(err),
                 }
+            }
+            _ => Self::Other(err),
+        }
+    }
+}
+
+impl QueryError {
+    /// Attaches the table `T` and the operation `op` the failed query ran with to this error. If
+    /// the error reports a violated constraint, the affected column is resolved back to the rust
+    /// field of `T` where possible.
+    pub fn within<T: Table>(mut self, op: Operation) -> Self {
+        if let Some(ctx) = self.context_mut() {
+            ctx.resolve::<T>(op);
+        }
+
+        self
+    }
+
+    /// Whether the query failed due to a transient conflict with concurrent transactions. Retrying
+    /// the failed query alone is not sufficient, the enclosing transaction has to be re-run.
+    pub const fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Sql(
+                SqlError::SerializationFailure(_)
+                    | SqlError::Deadlock(_)
+                    | SqlError::LockTimeout(_)
+            )
+        )
+    }
+
+    /// Access the context of this error, if the error is tied to a table
+    pub const fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::NotFound(_, ctx) => Some(ctx),
+            Self::Violation(v) => Some(v.context()),
+            _ => None,
+        }
+    }
+
+    fn context_mut(&mut self) -> Option<&mut ErrorContext> {
+        match self {
+            Self::NotFound(_, ctx) => Some(ctx),
+            Self::Violation(v) => Some(v.context_mut()),
+            _ => None,
+        }
+    }
+}
+
+/// Describes where a database error originated from.
+///
+/// The constraint and column are extracted from the driver error, while the table, operation and
+/// rust field are attached through [`QueryError::within`] by the atmosphere query that failed.
+/// This allows to turn errors into meaningful responses, e.g. "email already taken" for a
+/// uniqueness violation on the `email` field.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The schema of the table the query ran against
+    pub schema: Option<&'static str>,
+    /// The table the query ran against
+    pub table: Option<&'static str>,
+    /// The operation the query performed
+    pub op: Option<Operation>,
+    /// The name of the violated constraint (or index), as reported by the driver
+    pub constraint: Option<String>,
+    /// The sql name of the affected column
+    pub column: Option<String>,
+    /// The rust field name of the affected column
+    pub field: Option<&'static str>,
+}
+
+impl ErrorContext {
+    /// Extracts the constraint and column name from a driver error
+    fn inspect(err: &dyn DatabaseError) -> Self {
+        #[allow(unused_mut)]
+        let mut ctx = Self {
+            constraint: err.constraint().map(str::to_owned),
+            ..Self::default()
+        };
+
+        #[cfg(feature = "postgres")]
+        if let Some(pg) = err.try_downcast_ref::<sqlx::postgres::PgDatabaseError>() {
+            // postgres reports violated keys in the detail: `Key (email)=(..) already exists.`
+            ctx.column = pg
+                .column()
+                .or_else(|| pg.detail().and_then(|d| between(d, "Key (", ")=")))
+                .map(str::to_owned);
+        }
+
+        #[cfg(feature = "mysql")]
+        {
+            let msg = err.message();
 
-                // SQLSTATE code handling
-                // See https://en.wikipedia.org/wiki/SQLSTATE for reference
+            // Duplicate entry '..' for key 'user.email'
+            // .. a foreign key constraint fails (.., CONSTRAINT `post_ibfk_1` FOREIGN KEY (`author`) ..
+            // Check constraint 'name_chk' is violated.
+            ctx.constraint = between(msg, "for key '", "'")
+                .or_else(|| between(msg, "CONSTRAINT `", "`"))
+                .or_else(|| between(msg, "Check constraint '", "'"))
+                .map(str::to_owned);
 
-                if let Some(c) = e.code() {
-                    if c.len() < 5 {
-                        return Self::InternalError(err);
-                    }
+            ctx.column = between(msg, "FOREIGN KEY (`", "`)").map(str::to_owned);
+        }
+
+        #[cfg(feature = "sqlite")]
+        {
+            let msg = err.message();
 
-                    return match &c.as_ref()[0..1] {
-                        "22" => Self::Sql(SqlError::DataException(err)),
-                        "23" => Self::Sql(SqlError::IntegrityConstraint(err)),
-                        "42" => Self::Sql(SqlError::Syntax(err)),
-                        _ => Self::Sql(SqlError::Other(err)),
-                    };
+            // UNIQUE constraint failed: user.email
+            // CHECK constraint failed: name_chk
+            if let Some((_, failed)) = msg.split_once("constraint failed: ") {
+                if msg.starts_with("CHECK") {
+                    ctx.constraint = Some(failed.to_owned());
+                } else if let Some((_, column)) = failed.split_once('.') {
+                    ctx.column = Some(column.to_owned());
                 }
+            }
+        }
 
-                Self::Other(err)
+        if ctx.column.as_deref().is_some_and(|c| c.contains(',')) {
+            // composite keys can not be mapped onto a single field
+            ctx.column = None;
+        }
+
+        ctx
+    }
+
+    /// Attaches the table and operation and resolves the affected rust field of `T`
+    fn resolve<T: Table>(&mut self, op: Operation) {
+        self.schema = Some(T::SCHEMA);
+        self.table = Some(T::TABLE);
+        self.op = Some(op);
+
+        let columns = std::iter::once((T::PRIMARY_KEY.field, T::PRIMARY_KEY.sql))
+            .chain(T::FOREIGN_KEYS.iter().map(|c| (c.field, c.sql)))
+            .chain(T::DATA_COLUMNS.iter().map(|c| (c.field, c.sql)))
+            .chain(T::TIMESTAMP_COLUMNS.iter().map(|c| (c.field, c.sql)))
+            .chain(T::VERSION_COLUMN.iter().map(|c| (c.field, c.sql)));
+
+        for (field, sql) in columns {
+            let matches = match (&self.column, &self.constraint) {
+                (Some(column), _) => column == sql,
+                (None, Some(constraint)) => constraint_matches(constraint, T::TABLE, sql),
+                (None, None) => false,
+            };
+
+            if matches {
+                self.column = Some(sql.to_owned());
+                self.field = Some(field);
+                return;
             }
-            _ => Self::Other(err),
         }
     }
 }
 
+/// Checks whether `constraint` follows a default naming scheme for a constraint on `column`
+fn constraint_matches(constraint: &str, table: &str, column: &str) -> bool {
+    // mysql names unique indices after the column, prefixed by the table since 8.0
+    if constraint == column || constraint == format!("{table}.{column}") {
+        return true;
+    }
+
+    // postgres names constraints `<table>_<column>_<kind>`
+    ["key", "fkey", "pkey", "check"]
+        .iter()
+        .any(|kind| constraint == format!("{table}_{column}_{kind}"))
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+fn between<'s>(s: &'s str, start: &str, end: &str) -> Option<&'s str> {
+    let (_, rest) = s.split_once(start)?;
+    let (inner, _) = rest.split_once(end)?;
+    Some(inner)
+}
+
+/// Maps the error code of a driver error onto the `SqlError` taxonomy. Returns `None` if the
+/// reported code is malformed.
+#[cfg(feature = "postgres")]
+fn classify(err: &dyn DatabaseError) -> Option<fn(sqlx::Error) -> SqlError> {
+    sqlstate(&err.code()?)
+}
+
+/// Maps the error code of a driver error onto the `SqlError` taxonomy. Returns `None` if the
+/// reported code is malformed.
+///
+/// MySQL reports deadlocks as SQLSTATE `40001` and lock wait timeouts as the general `HY000`, so
+/// these are classified by their error number first.
+#[cfg(feature = "mysql")]
+fn classify(err: &dyn DatabaseError) -> Option<fn(sqlx::Error) -> SqlError> {
+    use sqlx::mysql::MySqlDatabaseError;
+
+    const ER_LOCK_WAIT_TIMEOUT: u16 = 1205;
+    const ER_LOCK_DEADLOCK: u16 = 1213;
+    const ER_LOCK_NOWAIT: u16 = 3572;
+
+    if let Some(mysql) = err.try_downcast_ref::<MySqlDatabaseError>() {
+        match mysql.number() {
+            ER_LOCK_DEADLOCK => return Some(SqlError::Deadlock),
+            ER_LOCK_WAIT_TIMEOUT | ER_LOCK_NOWAIT => return Some(SqlError::LockTimeout),
+            _ => {}
+        }
+    }
+
+    sqlstate(&err.code()?)
+}
+
+/// Maps the error code of a driver error onto the `SqlError` taxonomy. Returns `None` if the
+/// reported code is malformed.
+///
+/// SQLite reports extended result codes, see <https://www.sqlite.org/rescode.html>.
+#[cfg(feature = "sqlite")]
+fn classify(err: &dyn DatabaseError) -> Option<fn(sqlx::Error) -> SqlError> {
+    let code: i32 = err.code()?.parse().ok()?;
+
+    let msg = err.message();
+
+    let sql = match code {
+        // SQLITE_BUSY_SNAPSHOT: a read transaction can not be upgraded in WAL mode
+        517 => SqlError::SerializationFailure,
+        // the busy timeout elapsed waiting for SQLITE_BUSY or SQLITE_LOCKED
+        c if c & 0xff == 5 || c & 0xff == 6 => SqlError::LockTimeout,
+        // SQLITE_CONSTRAINT
+        c if c & 0xff == 19 => SqlError::IntegrityConstraint,
+        // SQLITE_TOOBIG, SQLITE_MISMATCH, SQLITE_RANGE
+        18 | 20 | 25 => SqlError::DataException,
+        // SQLITE_ERROR is generic, syntax errors are only identifiable by their message
+        1 if msg.contains("syntax error") || msg.starts_with("no such ") => SqlError::Syntax,
+        _ => SqlError::Other,
+    };
+
+    Some(sql)
+}
+
+/// Maps a SQLSTATE code onto the `SqlError` taxonomy.
+///
+/// See <https://en.wikipedia.org/wiki/SQLSTATE> for reference.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+fn sqlstate(code: &str) -> Option<fn(sqlx::Error) -> SqlError> {
+    if code.len() != 5 || !code.is_char_boundary(2) {
+        return None;
+    }
+
+    let sql = match code {
+        "40001" => SqlError::SerializationFailure,
+        "40P01" => SqlError::Deadlock,
+        "55P03" => SqlError::LockTimeout,
+        _ => match &code[0..2] {
+            "22" => SqlError::DataException,
+            "23" => SqlError::IntegrityConstraint,
+            "42" => SqlError::Syntax,
+            _ => SqlError::Other,
+        },
+    };
+
+    Some(sql)
+}
+
 /// Describes the cardinality of the rows affected by a query.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Cardinality {
@@ -169,11 +446,47 @@ pub enum Operation {
     Other,
 }
 
+/// The sql of a query, either generated once per table and cached for the lifetime of the
+/// program, or built for a single query.
+pub(crate) enum Sql {
+    Cached(&'static str),
+    Built(QueryBuilder<'static, crate::Driver>),
+}
+
+impl Sql {
+    pub(crate) fn sql(&self) -> &str {
+        match self {
+            Self::Cached(sql) => sql,
+            Self::Built(builder) => builder.sql(),
+        }
+    }
+
+    /// Appends `sql`, copying cached sql first.
+    #[cfg(not(feature = "sqlite"))]
+    pub(crate) fn push(&mut self, sql: impl std::fmt::Display) {
+        if let Self::Cached(cached) = self {
+            *self = Self::Built(QueryBuilder::new(*cached));
+        }
+
+        if let Self::Built(builder) = self {
+            builder.push(sql);
+        }
+    }
+
+    /// Converts the sql into a builder for appending further sql.
+    pub(crate) fn into_builder(self) -> QueryBuilder<'static, crate::Driver> {
+        match self {
+            Self::Cached(sql) => QueryBuilder::new(sql),
+            Self::Built(builder) => builder,
+        }
+    }
+}
+
 /// Represents a atmosphere query over a database table.
 pub struct Query<T: Bind> {
     pub op: Operation,
     pub cardinality: Cardinality,
-    pub(crate) builder: QueryBuilder<'static, crate::Driver>,
+    pub(crate) builder: Sql,
     pub(crate) bindings: Bindings<T>,
 }
 
@@ -187,7 +500,7 @@ impl<T: Bind> Query<T> {
         Self {
             op,
             cardinality,
-            builder,
+            builder: Sql::Built(builder),
             bindings,
         }
     }
@@ -210,3 +523,41 @@ pub enum QueryResult<'t, T: Table + Bind> {
     One(&'t Result<T>),
     Many(&'t Result<Vec<T>>),
 }
+
+#[cfg(test)]
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod tests {
+    use super::{sqlstate, SqlError};
+
+    fn class(code: &str) -> Option<SqlError> {
+        sqlstate(code).map(|sql| sql(sqlx::Error::RowNotFound))
+    }
+
+    #[test]
+    fn sqlstate_classes() {
+        assert!(matches!(class("22003"), Some(SqlError::DataException(_))));
+        assert!(matches!(
+            class("23502"),
+            Some(SqlError::IntegrityConstraint(_))
+        ));
+        assert!(matches!(class("42P01"), Some(SqlError::Syntax(_))));
+        assert!(matches!(class("08006"), Some(SqlError::Other(_))));
+    }
+
+    #[test]
+    fn sqlstate_concurrency() {
+        assert!(matches!(
+            class("40001"),
+            Some(SqlError::SerializationFailure(_))
+        ));
+        assert!(matches!(class("40P01"), Some(SqlError::Deadlock(_))));
+        assert!(matches!(class("55P03"), Some(SqlError::LockTimeout(_))));
+    }
+
+    #[test]
+    fn sqlstate_malformed() {
+        assert!(class("").is_none());
+        assert!(class("2").is_none());
+        assert!(class("220031").is_none());
+    }
+}
@@ -2,18 +2,36 @@
 //!
 //! This module contains traits and their implementations for handling relationships such as
 //! 'RefersTo' and 'ReferredBy'. These traits facilitate operations like resolving and deleting
-//! relationships in a database using SQLx.
+//! relationships in a database using SQLx, as well as joining related entities in a single query.
 
+use std::future::poll_fn;
+
+use async_stream::try_stream;
 use async_trait::async_trait;
+use futures_core::stream::BoxStream;
 use sqlx::database::HasArguments;
-use sqlx::{Executor, IntoArguments};
+use sqlx::{Database, Executor, IntoArguments, Row, ValueRef};
 
 use crate::bind::Bind;
+use crate::filter::Filter;
+use crate::hooks::{self, HookInput, HookStage, Hooks};
 use crate::query::{Query, QueryError};
-use crate::runtime::sql;
+use crate::runtime::sql::{self, Join};
 use crate::schema::Table;
 use crate::{Error, ForeignKey, Result};
 
+/// Decodes entities from rows of joins, whose columns are aliased as `"<alias>.<column>"` to avoid
+/// collisions between the columns of the joined tables.
+///
+/// Implemented by `#[derive(Schema)]`.
+pub trait FromAliasedRow: Sized {
+    /// Decodes an entity from the columns of `row` aliased with the table alias `alias`.
+    fn from_aliased_row(
+        row: &<crate::Driver as Database>::Row,
+        alias: &str,
+    ) -> std::result::Result<Self, sqlx::Error>;
+}
+
 /// Defines a relationship where `Self` refers to `Other`.
 ///
 /// Implements functionality to resolve this relationship, fetching the `Other` entity that `Self`
@@ -34,7 +52,7 @@ where
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send,
     {
-        let Query { builder, .. } = sql::select::<Other>();
+        let Query { op, builder, .. } = sql::select::<Other>();
 
         let mut query = sqlx::query_as(builder.sql());
 
@@ -42,10 +60,53 @@ where
         query = self.bind(&fk, query).unwrap();
 
         query
-            .persistent(false)
             .fetch_one(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<Other>(op))
+            .map_err(Error::Query)
+    }
+
+    /// Fetches all `Self` entities matching `filter` along with the `Other` entity they refer to,
+    /// using a single inner join. Hooks of `Self` are executed before binding and before
+    /// execution, without any row as input.
+    async fn join<'e, E>(filter: &Filter<Self>, executor: E) -> Result<Vec<(Self, Other)>>
+    where
+        Self: FromAliasedRow + Hooks + Send + Sync,
+        Other: FromAliasedRow + Send,
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = sql::join::<Self, Other>(
+            Join::Inner,
+            Self::FOREIGN_KEY.as_col(),
+            Other::PRIMARY_KEY.as_col(),
+            filter,
+        )?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let rows = sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
+            .map_err(Error::Query)?;
+
+        rows.iter()
+            .map(|row| {
+                Ok((
+                    Self::from_aliased_row(row, sql::JOIN_LEFT)?,
+                    Other::from_aliased_row(row, sql::JOIN_RIGHT)?,
+                ))
+            })
+            .collect::<std::result::Result<_, sqlx::Error>>()
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
             .map_err(Error::Query)
     }
 }
@@ -68,7 +129,7 @@ where
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send,
     {
-        let Query { builder, .. } = sql::select_by::<Other>(Other::FOREIGN_KEY.as_col());
+        let Query { op, builder, .. } = sql::select_by::<Other>(Other::FOREIGN_KEY.as_col());
 
         let mut query = sqlx::query_as(builder.sql());
 
@@ -76,10 +137,88 @@ where
         query = self.bind(&pk, query).unwrap();
 
         query
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Other>(op))
+            .map_err(Error::Query)
+    }
+
+    /// Like `resolve`, but streams the referring entities instead of collecting them. The query is
+    /// executed once the stream is polled.
+    fn resolve_stream<'e, E>(&'e self, executor: E) -> BoxStream<'e, Result<Other>>
+    where
+        E: Executor<'e, Database = crate::Driver> + 'e,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Box::pin(try_stream! {
+            let Query { op, builder, .. } = sql::select_by::<Other>(Other::FOREIGN_KEY.as_col());
+
+            let mut query = sqlx::query_as(builder.sql());
+
+            let pk = Self::PRIMARY_KEY.as_col();
+            query = self.bind(&pk, query).unwrap();
+
+            let mut rows = query.fetch(executor);
+
+            while let Some(row) = poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+                yield row
+                    .map_err(|e| QueryError::from(e).within::<Other>(op))
+                    .map_err(Error::Query)?;
+            }
+        })
+    }
+
+    /// Fetches all `Self` entities matching `filter` along with the `Other` entities referring to
+    /// them, using a single left join. Every `Self` entity is returned once per referring entity,
+    /// or once with `None` if no entity refers to it. Hooks of `Self` are executed like in
+    /// `RefersTo::join`.
+    async fn left_join<'e, E>(
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Vec<(Self, Option<Other>)>>
+    where
+        Self: FromAliasedRow + Hooks + Send,
+        Other: FromAliasedRow + Send,
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = sql::join::<Self, Other>(
+            Join::Left,
+            Self::PRIMARY_KEY.as_col(),
+            Other::FOREIGN_KEY.as_col(),
+            filter,
+        )?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let rows = sqlx::query_with(query.sql(), args)
             .persistent(false)
             .fetch_all(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
+            .map_err(Error::Query)?;
+
+        // the primary key of a joined row is only null if there is no joined row
+        let pk = format!("{}.{}", sql::JOIN_RIGHT, Other::PRIMARY_KEY.sql);
+
+        rows.iter()
+            .map(|row| {
+                let other = match row.try_get_raw(pk.as_str())?.is_null() {
+                    true => None,
+                    false => Some(Other::from_aliased_row(row, sql::JOIN_RIGHT)?),
+                };
+
+                Ok((Self::from_aliased_row(row, sql::JOIN_LEFT)?, other))
+            })
+            .collect::<std::result::Result<_, sqlx::Error>>()
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
             .map_err(Error::Query)
     }
 
@@ -90,14 +229,13 @@ where
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send,
     {
-        let Query { builder, .. } = sql::select_by::<Other>(Other::FOREIGN_KEY.as_col());
+        let Query { op, builder, .. } = sql::select_by::<Other>(Other::FOREIGN_KEY.as_col());
 
         sqlx::query_as(builder.sql())
             .bind(pk)
-            .persistent(false)
             .fetch_all(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<Other>(op))
             .map_err(Error::Query)
     }
 
@@ -111,7 +249,7 @@ where
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send,
     {
-        let Query { builder, .. } = sql::delete_by::<Other>(Other::FOREIGN_KEY.as_col());
+        let Query { op, builder, .. } = sql::delete_by::<Other>(Other::FOREIGN_KEY.as_col());
 
         let mut query = sqlx::query(builder.sql());
 
@@ -119,10 +257,9 @@ where
         query = self.bind(&pk, query).unwrap();
 
         query
-            .persistent(false)
             .execute(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<Other>(op))
             .map_err(Error::Query)
     }
 }
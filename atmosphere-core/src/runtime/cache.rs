@@ -0,0 +1,70 @@
+//! Statement Cache
+//!
+//! The sql of statements whose shape only depends on their table (such as the statements of
+//! `Read::find` or `Create::create`) is generated once per table and kept for the lifetime of the
+//! program. Cached queries borrow their sql and bindings, which removes formatting and allocations
+//! from the hot paths of the CRUD traits.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use crate::query::{Cardinality, Operation, Query, Sql};
+use crate::runtime::sql::Bindings;
+use crate::{Bind, Column};
+
+/// A generated statement of table `T`
+struct Statement<T: Bind> {
+    op: Operation,
+    cardinality: Cardinality,
+    sql: &'static str,
+    bindings: &'static [Column<T>],
+}
+
+type Statements = HashMap<(TypeId, &'static str), &'static (dyn Any + Send + Sync)>;
+
+static STATEMENTS: OnceLock<RwLock<Statements>> = OnceLock::new();
+
+/// Returns the statement `name` of table `T`, generating it using `generate` on first use.
+pub(crate) fn cached<T: Bind>(name: &'static str, generate: impl FnOnce() -> Query<T>) -> Query<T> {
+    let statements = STATEMENTS.get_or_init(Default::default);
+    let key = (TypeId::of::<T>(), name);
+
+    let cached = statements
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+        .copied();
+
+    let statement = match cached {
+        Some(statement) => statement,
+        None => {
+            let mut statements = statements.write().unwrap_or_else(PoisonError::into_inner);
+
+            // the statement may have been generated while waiting for the lock
+            *statements.entry(key).or_insert_with(|| {
+                let query = generate();
+
+                let statement: &'static Statement<T> = Box::leak(Box::new(Statement {
+                    op: query.op,
+                    cardinality: query.cardinality,
+                    sql: Box::leak(query.sql().into()),
+                    bindings: Box::leak(query.bindings().columns().into()),
+                }));
+
+                statement
+            })
+        }
+    };
+
+    let statement = statement
+        .downcast_ref::<Statement<T>>()
+        .expect("statements are keyed by the type id of their table");
+
+    Query {
+        op: statement.op,
+        cardinality: statement.cardinality,
+        builder: Sql::Cached(statement.sql),
+        bindings: Bindings::cached(statement.bindings),
+    }
+}
@@ -7,5 +7,6 @@
 //! execution of queries, handling connections, and managing transactions. It acts as the backbone
 //! of the framework, ensuring smooth and efficient operations with the database at runtime.
 
+mod cache;
 /// SQL code generator
 pub mod sql;
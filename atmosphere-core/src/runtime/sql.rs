@@ -15,13 +15,17 @@
 //!   table columns and the SQL queries they are bound to. This ensures that queries are executed with the correct
 //!   parameters and their values.
 
+use std::borrow::Cow;
 use std::fmt;
 
 use sqlx::QueryBuilder;
 
 use crate::{
+    filter::{Assignments, Filter},
     query::{self, Query},
-    Bind, Column,
+    runtime::cache,
+    Aggregation, Bind, BindError, Column, ColumnDefinition, DataColumn, Ddl, Error, Result, Table,
+    Upsert, VersionColumn,
 };
 
 /// Struct representing bindings for SQL queries.
@@ -29,7 +33,7 @@ use crate::{
 /// `Bindings` is responsible for holding a collection of columns that are bound to a specific SQL query.
 /// It encapsulates the necessary details for each column, such as field names and SQL representations,
 /// ensuring accurate and efficient binding of data to the query.
-pub struct Bindings<T: Bind>(Vec<Column<T>>);
+pub struct Bindings<T: Bind>(Cow<'static, [Column<T>]>);
 
 impl<T: Bind> PartialEq for Bindings<T> {
     fn eq(&self, other: &Self) -> bool {
@@ -61,7 +65,7 @@ impl<T: Bind> fmt::Debug for Bindings<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut f = f.debug_tuple("Bindings");
 
-        for c in &self.0 {
+        for c in self.0.iter() {
             f.field(&c.field());
         }
 
@@ -75,23 +79,45 @@ impl<T: Bind> Bindings<T> {
     }
 
     pub fn empty() -> Self {
-        Self(vec![])
+        Self(Cow::Borrowed(&[]))
+    }
+
+    pub(crate) const fn cached(columns: &'static [Column<T>]) -> Self {
+        Self(Cow::Borrowed(columns))
+    }
+}
+
+impl<T: Bind> From<Vec<Column<T>>> for Bindings<T> {
+    fn from(columns: Vec<Column<T>>) -> Self {
+        Self(Cow::Owned(columns))
+    }
+}
+
+fn version<T: Bind>() -> Option<&'static VersionColumn<T>> {
+    match &T::VERSION_COLUMN {
+        Some(version) => Some(version),
+        None => None,
     }
 }
 
-fn table<T: Bind>() -> String {
+pub(crate) fn table<T: Table>() -> String {
+    qualified(T::SCHEMA, T::TABLE)
+}
+
+#[cfg_attr(feature = "sqlite", allow(unused_variables))]
+fn qualified(schema: &str, table: &str) -> String {
     #[cfg(not(feature = "sqlite"))]
-    return format!("\"{}\".\"{}\"", T::SCHEMA, T::TABLE);
+    return format!("\"{schema}\".\"{table}\"");
 
     #[cfg(feature = "sqlite")]
-    return format!("\"{}\"", T::TABLE);
+    return format!("\"{table}\"");
 }
 
 /// Generates a `SELECT` query to retrieve a single row from the table based on its primary key.
 ///
 /// SQL: `SELECT * FROM .. WHERE .. = $1`
 pub fn select<T: Bind>() -> Query<T> {
-    select_by(Column::PrimaryKey(&T::PRIMARY_KEY))
+    cache::cached("select", || select_by(Column::PrimaryKey(&T::PRIMARY_KEY)))
 }
 
 /// Creates a `SELECT` query to retrieve rows from the table based on a specific column.
@@ -112,6 +138,10 @@ pub fn select_by<T: Bind>(c: Column<T>) -> Query<T> {
         separated.push(data.sql);
     }
 
+    if let Some(version) = version::<T>() {
+        separated.push(version.sql);
+    }
+
     for meta in T::TIMESTAMP_COLUMNS {
         separated.push(meta.sql);
     }
@@ -123,33 +153,392 @@ pub fn select_by<T: Bind>(c: Column<T>) -> Query<T> {
         query::Operation::Select,
         query::Cardinality::One,
         query,
-        Bindings(vec![c]),
+        Bindings::from(vec![c]),
     )
 }
 
+/// Describes the row lock a `SELECT` query acquires for the enclosing transaction.
+///
+/// Not available with the `sqlite` driver, as SQLite only supports locking the whole database.
+#[cfg(not(feature = "sqlite"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lock {
+    /// Locks the selected rows, waiting for concurrent locks to be released
+    ForUpdate,
+    /// Locks the selected rows, skipping rows locked by concurrent transactions
+    ForUpdateSkipLocked,
+}
+
+#[cfg(not(feature = "sqlite"))]
+impl Lock {
+    const fn sql(self) -> &'static str {
+        match self {
+            Self::ForUpdate => "FOR UPDATE",
+            Self::ForUpdateSkipLocked => "FOR UPDATE SKIP LOCKED",
+        }
+    }
+}
+
+/// Creates a `SELECT` query to retrieve and lock rows from the table based on a specific column.
+///
+/// SQL: `SELECT * FROM .. WHERE .. = $1 FOR UPDATE [SKIP LOCKED]`
+#[cfg(not(feature = "sqlite"))]
+pub fn select_by_locked<T: Bind>(c: Column<T>, lock: Lock) -> Query<T> {
+    let mut query = select_by(c);
+    query.builder.push(format!("\n{}", lock.sql()));
+    query
+}
+
+/// Constructs a `SELECT` query to fetch and lock all rows from the table.
+///
+/// SQL: `SELECT * FROM .. FOR UPDATE [SKIP LOCKED]`
+#[cfg(not(feature = "sqlite"))]
+pub fn select_all_locked<T: Bind>(lock: Lock) -> Query<T> {
+    let mut query = select_all();
+    query.builder.push(lock.sql());
+    query
+}
+
 /// Constructs a `SELECT` query to fetch all rows from the table.
 ///
 /// SQL: `SELECT * FROM ..`
 pub fn select_all<T: Bind>() -> Query<T> {
+    cache::cached("select_all", || {
+        let mut query = QueryBuilder::new("SELECT\n  ");
+
+        let mut separated = query.separated(",\n  ");
+
+        separated.push(T::PRIMARY_KEY.sql);
+
+        for fk in T::FOREIGN_KEYS {
+            separated.push(fk.sql);
+        }
+
+        for data in T::DATA_COLUMNS {
+            separated.push(data.sql);
+        }
+
+        if let Some(version) = version::<T>() {
+            separated.push(version.sql);
+        }
+
+        for meta in T::TIMESTAMP_COLUMNS {
+            separated.push(meta.sql);
+        }
+
+        query.push(format!("\nFROM\n  {}\n", table::<T>()));
+
+        Query::new(
+            query::Operation::Select,
+            query::Cardinality::Many,
+            query,
+            Bindings::empty(),
+        )
+    })
+}
+
+/// Creates a `SELECT` query checking whether a row with a specific column value exists, without
+/// retrieving any of its columns. The query results in at most one row.
+///
+/// SQL: `SELECT 1 FROM .. WHERE .. = $1 LIMIT 1`
+pub fn exists_by<T: Bind>(c: Column<T>) -> Query<T> {
+    let query = QueryBuilder::new(format!(
+        "SELECT 1 FROM {} WHERE {} = $1 LIMIT 1",
+        table::<T>(),
+        c.sql()
+    ));
+
+    Query::new(
+        query::Operation::Select,
+        query::Cardinality::One,
+        query,
+        Bindings::from(vec![c]),
+    )
+}
+
+/// Generates a `SELECT` query checking whether any row matches `filter`, without retrieving any
+/// of its columns. The query results in at most one row, the values are bound from the filter
+/// instead of an entity.
+///
+/// SQL: `SELECT 1 FROM .. WHERE .. LIMIT 1`
+pub fn exists_where<T: Bind>(filter: &Filter<T>) -> Result<Query<T>> {
+    let mut query = QueryBuilder::new(format!("SELECT 1 FROM {}", table::<T>()));
+
+    if let Some(condition) = filter.condition(&mut 0)? {
+        query.push(format!(" WHERE {condition}"));
+    }
+
+    query.push(" LIMIT 1");
+
+    Ok(Query::new(
+        query::Operation::Select,
+        query::Cardinality::One,
+        query,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `SELECT` query computing an aggregate over the rows matching `filter`. The
+/// values are bound from the filter instead of an entity.
+///
+/// SQL: `SELECT count(*) FROM .. WHERE ..`
+pub fn aggregate<T: Bind, A>(
+    aggregation: &Aggregation<T, A>,
+    filter: &Filter<T>,
+) -> Result<Query<T>> {
+    let mut query = QueryBuilder::new(format!(
+        "SELECT\n  {}\nFROM\n  {}\n",
+        aggregation.sql(),
+        table::<T>()
+    ));
+
+    if let Some(condition) = filter.condition(&mut 0)? {
+        query.push(format!("WHERE\n  {condition}\n"));
+    }
+
+    Ok(Query::new(
+        query::Operation::Select,
+        query::Cardinality::One,
+        query,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `SELECT` query grouping the rows matching `filter` by `columns` and computing an
+/// aggregate per group, ordered by the grouped columns. The values are bound from the filter
+/// instead of an entity.
+///
+/// SQL: `SELECT .., count(*) FROM .. WHERE .. GROUP BY .. ORDER BY ..`
+pub fn group_by<T: Bind, A>(
+    columns: &[&Column<T>],
+    aggregation: &Aggregation<T, A>,
+    filter: &Filter<T>,
+) -> Result<Query<T>> {
+    let columns: Vec<&str> = columns.iter().map(|c| c.sql()).collect();
+    let columns = columns.join(", ");
+
+    let mut query = QueryBuilder::new(format!(
+        "SELECT\n  {columns},\n  {}\nFROM\n  {}\n",
+        aggregation.sql(),
+        table::<T>()
+    ));
+
+    if let Some(condition) = filter.condition(&mut 0)? {
+        query.push(format!("WHERE\n  {condition}\n"));
+    }
+
+    query.push(format!("GROUP BY {columns}\nORDER BY {columns}\n"));
+
+    Ok(Query::new(
+        query::Operation::Select,
+        query::Cardinality::Many,
+        query,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `SELECT` query retrieving `columns` of the rows matching `filter`. The values are
+/// bound from the filter instead of an entity.
+///
+/// SQL: `SELECT .. FROM .. WHERE ..`
+pub fn project<T: Bind>(columns: &[Column<T>], filter: &Filter<T>) -> Result<Query<T>> {
+    let mut query = projection(columns);
+
+    if let Some(condition) = filter.condition(&mut 0)? {
+        query.push(format!("WHERE\n  {condition}\n"));
+    }
+
+    Ok(Query::new(
+        query::Operation::Select,
+        query::Cardinality::Many,
+        query,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `SELECT` query retrieving `columns` of the row identified by a specific column.
+///
+/// SQL: `SELECT .. FROM .. WHERE .. = $1`
+pub fn project_by<T: Bind>(columns: &[Column<T>], c: Column<T>) -> Query<T> {
+    let mut query = projection(columns);
+
+    query.push(format!("WHERE {} = $1", c.sql()));
+
+    Query::new(
+        query::Operation::Select,
+        query::Cardinality::One,
+        query,
+        Bindings::from(vec![c]),
+    )
+}
+
+fn projection<T: Bind>(columns: &[Column<T>]) -> QueryBuilder<'static, crate::Driver> {
     let mut query = QueryBuilder::new("SELECT\n  ");
 
     let mut separated = query.separated(",\n  ");
 
-    separated.push(T::PRIMARY_KEY.sql);
+    for c in columns {
+        separated.push(c.sql());
+    }
 
-    for fk in T::FOREIGN_KEYS {
-        separated.push(fk.sql);
+    query.push(format!("\nFROM\n  {}\n", table::<T>()));
+
+    query
+}
+
+/// Describes how the rows of two tables are joined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Join {
+    /// Only selects rows having a matching row in the joined table
+    Inner,
+    /// Selects all rows, along with the matching rows of the joined table (if any)
+    Left,
+}
+
+impl Join {
+    const fn sql(self) -> &'static str {
+        match self {
+            Self::Inner => "JOIN",
+            Self::Left => "LEFT JOIN",
+        }
     }
+}
 
-    for data in T::DATA_COLUMNS {
-        separated.push(data.sql);
+/// The alias of the selecting table of joins
+pub const JOIN_LEFT: &str = "t0";
+
+/// The alias of the joined table of joins
+pub const JOIN_RIGHT: &str = "t1";
+
+/// The sql names of all columns of table `T`, in the column order of `select`.
+fn columns<T: Bind>() -> Vec<&'static str> {
+    let mut columns = vec![T::PRIMARY_KEY.sql];
+
+    columns.extend(T::FOREIGN_KEYS.iter().map(|fk| fk.sql));
+    columns.extend(T::DATA_COLUMNS.iter().map(|data| data.sql));
+    columns.extend(version::<T>().map(|version| version.sql));
+    columns.extend(T::TIMESTAMP_COLUMNS.iter().map(|meta| meta.sql));
+
+    columns
+}
+
+/// Selects all columns of table `T`, qualified by and aliased with the table alias `alias`.
+fn aliased<T: Bind>(alias: &str) -> Vec<String> {
+    columns::<T>()
+        .into_iter()
+        .map(|c| format!("\"{alias}\".{c} AS \"{alias}.{c}\""))
+        .collect()
+}
+
+/// Generates a `SELECT` query joining the rows of table `A` matching `filter` with the rows of
+/// table `B` whose column `b` equals column `a`. The columns of `A` are selected as
+/// `"t0.<column>"`, the columns of `B` as `"t1.<column>"` (see [`JOIN_LEFT`] and
+/// [`JOIN_RIGHT`]), which avoids collisions of columns of the same name. The values are bound from
+/// the filter instead of an entity.
+///
+/// SQL: `SELECT .. FROM .. AS "t0" [LEFT] JOIN .. AS "t1" ON "t0".. = "t1".. WHERE ..`
+pub fn join<A: Bind, B: Bind>(
+    join: Join,
+    a: Column<A>,
+    b: Column<B>,
+    filter: &Filter<A>,
+) -> Result<Query<A>> {
+    let mut columns = aliased::<A>(JOIN_LEFT);
+    columns.extend(aliased::<B>(JOIN_RIGHT));
+
+    let mut query = QueryBuilder::new(format!(
+        "SELECT\n  {}\nFROM\n  {} AS \"{JOIN_LEFT}\"\n{} {} AS \"{JOIN_RIGHT}\" ON \"{JOIN_LEFT}\".{} = \"{JOIN_RIGHT}\".{}\n",
+        columns.join(",\n  "),
+        table::<A>(),
+        join.sql(),
+        table::<B>(),
+        a.sql(),
+        b.sql(),
+    ));
+
+    let qualifier = format!("\"{JOIN_LEFT}\".");
+
+    if let Some(condition) = filter.qualified_condition(&mut 0, &qualifier)? {
+        query.push(format!("WHERE\n  {condition}\n"));
     }
 
-    for meta in T::TIMESTAMP_COLUMNS {
-        separated.push(meta.sql);
+    Ok(Query::new(
+        query::Operation::Select,
+        query::Cardinality::Many,
+        query,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `SELECT` query searching `columns` for the text bound as `$1` using the full-text
+/// search of the database, ordering the matching rows by their relevance.
+///
+/// SQL (postgres): `SELECT .. FROM .. WHERE to_tsvector(..) @@ websearch_to_tsquery($1) ORDER BY
+/// ts_rank(..) DESC`
+///
+/// SQL (sqlite): `SELECT .. FROM .. JOIN .._fts ON .. WHERE .._fts MATCH $1 ORDER BY rank`
+///
+/// SQL (mysql): `SELECT .. FROM .. WHERE MATCH (..) AGAINST ($1) ORDER BY MATCH (..) AGAINST ($1)
+/// DESC`
+pub fn search<T: Bind>(columns: &[DataColumn<T>], config: &str) -> Query<T> {
+    let table = table::<T>();
+
+    let selected: Vec<String> = self::columns::<T>()
+        .into_iter()
+        .map(|c| format!("{table}.{c}"))
+        .collect();
+
+    let mut query = QueryBuilder::new(format!(
+        "SELECT\n  {}\nFROM\n  {table}\n",
+        selected.join(",\n  ")
+    ));
+
+    #[cfg(feature = "postgres")]
+    {
+        let document: Vec<String> = columns
+            .iter()
+            .map(|c| format!("coalesce({table}.{}, '')", c.sql))
+            .collect();
+
+        // the explicit configuration makes the functions immutable, which indexes require
+        let config = format!("'{}'::regconfig", config.replace('\'', "''"));
+
+        let document = format!("to_tsvector({config}, {})", document.join(" || ' ' || "));
+        let search = format!("websearch_to_tsquery({config}, $1)");
+
+        query.push(format!(
+            "WHERE {document} @@ {search}\nORDER BY ts_rank({document}, {search}) DESC"
+        ));
     }
 
-    query.push(format!("\nFROM\n  {}\n", table::<T>()));
+    #[cfg(feature = "sqlite")]
+    {
+        // the fts5 table indexes the searchable columns, which are not selected from it
+        let _ = (columns, config);
+
+        let fts = format!("\"{}_fts\"", T::TABLE);
+
+        query.push(format!(
+            "JOIN {fts} ON {fts}.rowid = {table}.rowid\nWHERE {fts} MATCH $1\nORDER BY {fts}.rank"
+        ));
+    }
+
+    #[cfg(feature = "mysql")]
+    {
+        let _ = config;
+
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|c| format!("{table}.{}", c.sql))
+            .collect();
+
+        let score = format!(
+            "MATCH ({}) AGAINST ($1 IN NATURAL LANGUAGE MODE)",
+            columns.join(", ")
+        );
+
+        query.push(format!("WHERE {score}\nORDER BY {score} DESC"));
+    }
 
     Query::new(
         query::Operation::Select,
@@ -159,10 +548,90 @@ pub fn select_all<T: Bind>() -> Query<T> {
     )
 }
 
+/// Interpolates the identifiers of table `T` into raw sql: `{table}` is replaced by the qualified
+/// name of the table and `{<field>}` by the sql name of the column of the rust field `<field>`.
+/// Literal braces are escaped as `{{` and `}}`. Fails for unknown fields and unterminated braces.
+///
+/// SQL: `SELECT * FROM {table} WHERE {name} = $1` ⟶ `SELECT * FROM "public"."user" WHERE name = $1`
+pub fn interpolate<T: Bind>(sql: &str) -> Result<String> {
+    let mut interpolated = String::with_capacity(sql.len());
+    let mut rest = sql;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        interpolated.push_str(&rest[..i]);
+
+        let (brace, tail) = rest[i..].split_at(1);
+
+        if let Some(tail) = tail.strip_prefix(brace) {
+            interpolated.push_str(brace);
+            rest = tail;
+            continue;
+        }
+
+        let Some((ident, tail)) = tail.split_once('}').filter(|_| brace == "{") else {
+            return Err(Error::Bind(BindError::Identifier(rest[i..].to_owned())));
+        };
+
+        match ident {
+            "table" => interpolated.push_str(&table::<T>()),
+            field => match Column::<T>::by_field(field) {
+                Some(c) => interpolated.push_str(c.sql()),
+                None => return Err(Error::Bind(BindError::Identifier(field.to_owned()))),
+            },
+        }
+
+        rest = tail;
+    }
+
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+/// The maximum number of parameters the driver allows to bind to a single statement.
+#[cfg(feature = "postgres")]
+pub const MAX_BINDINGS: usize = 65535;
+
+/// The maximum number of parameters the driver allows to bind to a single statement.
+#[cfg(feature = "mysql")]
+pub const MAX_BINDINGS: usize = 65535;
+
+/// The maximum number of parameters the driver allows to bind to a single statement.
+#[cfg(feature = "sqlite")]
+pub const MAX_BINDINGS: usize = 32766;
+
 /// Generates an `INSERT` query to add a new row to the table.
 ///
 /// SQL: `INSERT INTO .. VALUES ..`
 pub fn insert<T: Bind>() -> Query<T> {
+    cache::cached("insert", || insert_into(1, false))
+}
+
+/// Generates an `INSERT` query to add `rows` new rows to the table in a single statement. The
+/// bindings describe a single row and are to be bound once per row, in order.
+///
+/// The number of rows is limited by [`MAX_BINDINGS`], see [`insert_many_chunk`].
+///
+/// SQL: `INSERT INTO .. VALUES (..), (..)`
+pub fn insert_many<T: Bind>(rows: usize) -> Query<T> {
+    insert_into(rows, false)
+}
+
+/// The maximum number of rows a single `insert_many` query can insert without exceeding the
+/// bind parameter limit of the driver.
+pub fn insert_many_chunk<T: Bind>() -> usize {
+    let columns = 1
+        + T::FOREIGN_KEYS.len()
+        + T::DATA_COLUMNS.len()
+        + T::TIMESTAMP_COLUMNS.len()
+        + usize::from(T::VERSION_COLUMN.is_some());
+
+    (MAX_BINDINGS / columns).max(1)
+}
+
+/// Builds an `INSERT` query for `rows` rows, optionally storing the incremented version of the
+/// inserted rows.
+fn insert_into<T: Bind>(rows: usize, increment_version: bool) -> Query<T> {
     let mut builder = QueryBuilder::new(format!("INSERT INTO {}\n  (", table::<T>()));
 
     let mut bindings = vec![];
@@ -182,114 +651,268 @@ pub fn insert<T: Bind>() -> Query<T> {
         bindings.push(Column::Data(data));
     }
 
+    if let Some(version) = version::<T>() {
+        separated.push(version.sql.to_string());
+        bindings.push(Column::Version(version));
+    }
+
     for meta in T::TIMESTAMP_COLUMNS {
         separated.push(meta.sql.to_string());
         bindings.push(Column::Timestamp(meta));
     }
 
-    separated.push_unseparated(")\nVALUES\n  (");
+    builder.push(")\nVALUES\n  ");
 
-    separated.push_unseparated("$1");
+    for row in 0..rows {
+        if row > 0 {
+            builder.push(",\n  ");
+        }
+
+        let offset = row * bindings.len();
 
-    let columns = 1 + T::FOREIGN_KEYS.len() + T::DATA_COLUMNS.len() + T::TIMESTAMP_COLUMNS.len();
+        let values: Vec<String> = bindings
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match c {
+                Column::Version(_) if increment_version => format!("${} + 1", offset + i + 1),
+                _ => format!("${}", offset + i + 1),
+            })
+            .collect();
 
-    for c in 2..=columns {
-        separated.push(format!("${c}"));
+        builder.push(format!("({})", values.join(", ")));
     }
 
-    builder.push(")");
+    let cardinality = match rows {
+        1 => query::Cardinality::One,
+        _ => query::Cardinality::Many,
+    };
 
     Query::new(
         query::Operation::Insert,
-        query::Cardinality::One,
+        cardinality,
         builder,
-        Bindings(bindings),
+        Bindings::from(bindings),
     )
 }
 
-/// Creates an `UPDATE` query to modify an existing row in the table.
+/// Generates an `INSERT` query which only provides the given columns, leaving all other columns
+/// to their database defaults, and returns the inserted row. If the table has a version column,
+/// it is initialized with `0`.
 ///
-/// SQL: `UPDATE .. SET .. WHERE ..`
-pub fn update<T: Bind>() -> Query<T> {
-    let mut builder = QueryBuilder::new(format!("UPDATE {} SET\n  ", table::<T>()));
-    let mut bindings = vec![];
+/// Not available with the `mysql` driver, as MySQL does not support `RETURNING`.
+///
+/// SQL: `INSERT INTO .. VALUES .. RETURNING ..`
+#[cfg(not(feature = "mysql"))]
+pub fn insert_returning<T: Bind>(columns: impl IntoIterator<Item = Column<T>>) -> Query<T> {
+    let mut builder = QueryBuilder::new(format!("INSERT INTO {}\n", table::<T>()));
+
+    let bindings: Vec<Column<T>> = columns
+        .into_iter()
+        .filter(|c| !matches!(c, Column::Version(_)))
+        .collect();
+
+    let mut names: Vec<&str> = bindings.iter().map(|c| c.sql()).collect();
+    let mut values: Vec<String> = (1..=bindings.len()).map(|i| format!("${i}")).collect();
+
+    if let Some(version) = version::<T>() {
+        names.push(version.sql);
+        values.push("0".to_owned());
+    }
 
-    let mut separated = builder.separated(",\n  ");
+    if names.is_empty() {
+        builder.push("DEFAULT VALUES");
+    } else {
+        builder.push(format!(
+            "  ({})\nVALUES\n  ({})",
+            names.join(", "),
+            values.join(", ")
+        ));
+    }
 
-    separated.push(format!("{} = $1", T::PRIMARY_KEY.sql));
-    bindings.push(Column::PrimaryKey(&T::PRIMARY_KEY));
+    builder.push("\nRETURNING\n  ");
 
-    let mut col = 2;
+    let mut separated = builder.separated(",\n  ");
+
+    separated.push(T::PRIMARY_KEY.sql);
 
     for fk in T::FOREIGN_KEYS {
-        separated.push(format!("{} = ${col}", fk.sql));
-        bindings.push(Column::ForeignKey(fk));
-        col += 1;
+        separated.push(fk.sql);
     }
 
     for data in T::DATA_COLUMNS {
-        separated.push(format!("{} = ${col}", data.sql));
-        bindings.push(Column::Data(data));
-        col += 1;
+        separated.push(data.sql);
+    }
+
+    if let Some(version) = version::<T>() {
+        separated.push(version.sql);
     }
 
     for meta in T::TIMESTAMP_COLUMNS {
-        separated.push(format!("{} = ${col}", meta.sql));
-        bindings.push(Column::Timestamp(meta));
-        col += 1;
+        separated.push(meta.sql);
+    }
+
+    Query::new(
+        query::Operation::Insert,
+        query::Cardinality::One,
+        builder,
+        Bindings::from(bindings),
+    )
+}
+
+/// Creates an `UPDATE` query to modify an existing row in the table. If the table has a version
+/// column, it is incremented and the row is only updated if its version matches the binding.
+///
+/// SQL: `UPDATE .. SET .. WHERE ..`
+pub fn update<T: Bind>() -> Query<T> {
+    cache::cached("update", || {
+        let columns = std::iter::once(Column::PrimaryKey(&T::PRIMARY_KEY))
+            .chain(T::FOREIGN_KEYS.iter().map(Column::ForeignKey))
+            .chain(T::DATA_COLUMNS.iter().map(Column::Data))
+            .chain(T::TIMESTAMP_COLUMNS.iter().map(Column::Timestamp));
+
+        update_columns(columns)
+    })
+}
+
+/// Creates an `UPDATE` query which only sets the given columns of an existing row, leaving all
+/// other columns untouched. The primary key is always bound as `$1`; version columns are handled
+/// like in [`update`] and are therefore skipped if passed. The columns must not be empty, as
+/// `UPDATE .. SET` requires at least one assignment.
+///
+/// SQL: `UPDATE .. SET .. WHERE ..`
+pub fn update_columns<T: Bind>(columns: impl IntoIterator<Item = Column<T>>) -> Query<T> {
+    update_set(columns, true)
+}
+
+/// Creates an `UPDATE` query which sets the given columns of a row identified by its primary key
+/// only, as used for applying patches. If the table has a version column, it is incremented
+/// without checking it, which lets concurrent holders of the row detect the change.
+///
+/// SQL: `UPDATE .. SET .. WHERE ..`
+pub fn patch<T: Bind>(columns: impl IntoIterator<Item = Column<T>>) -> Query<T> {
+    update_set(columns, false)
+}
+
+fn update_set<T: Bind>(
+    columns: impl IntoIterator<Item = Column<T>>,
+    check_version: bool,
+) -> Query<T> {
+    let mut builder = QueryBuilder::new(format!("UPDATE {} SET\n  ", table::<T>()));
+    let mut bindings = vec![Column::PrimaryKey(&T::PRIMARY_KEY)];
+
+    let mut separated = builder.separated(",\n  ");
+
+    let mut col = 2;
+
+    for c in columns {
+        match c {
+            Column::PrimaryKey(pk) => {
+                separated.push(format!("{} = $1", pk.sql));
+            }
+            Column::Version(_) => {}
+            c => {
+                separated.push(format!("{} = ${col}", c.sql()));
+                bindings.push(c);
+                col += 1;
+            }
+        }
+    }
+
+    if let Some(version) = version::<T>() {
+        separated.push(format!("{0} = {0} + 1", version.sql));
     }
 
     builder.push(format!("\nWHERE\n  {} = $1", T::PRIMARY_KEY.sql));
 
+    // optimistic concurrency control: only update the row if it was not modified in the meantime
+    if let Some(version) = version::<T>().filter(|_| check_version) {
+        builder.push(format!(" AND {} = ${col}", version.sql));
+        bindings.push(Column::Version(version));
+    }
+
     Query::new(
         query::Operation::Update,
         query::Cardinality::One,
         builder,
-        Bindings(bindings),
+        Bindings::from(bindings),
     )
 }
 
-/// Constructs an `UPSERT` query (update or insert) for a row in the table.
+/// Constructs an `UPSERT` query (update or insert) for a row in the table. If the table has a
+/// version column, the stored version is the incremented version of the binding and conflicting
+/// rows are only updated if their version matches the binding.
 ///
 /// SQL: `UPDATE .. SET .. WHERE .. ON CONFLICT .. DO UPDATE SET`
 pub fn upsert<T: Bind>() -> Query<T> {
+    cache::cached("upsert", || {
+        upsert_with(1, &Upsert::default()).expect("upserting all columns is infallible")
+    })
+}
+
+/// Constructs an `UPSERT` query for `rows` rows, resolving conflicts as described by `upsert`.
+/// The bindings describe a single row and are to be bound once per row, in order.
+///
+/// Versions are handled like in [`upsert`], unless conflicts are ignored: then, the version of
+/// inserted rows is stored as is. Fails if `upsert` refers to unknown fields.
+///
+/// SQL: `INSERT INTO .. VALUES (..), (..) ON CONFLICT .. DO [UPDATE SET .. | NOTHING]`
+pub fn upsert_with<T: Bind>(rows: usize, upsert: &Upsert<T>) -> Result<Query<T>> {
+    let columns = upsert.columns()?;
+
     let Query {
-        mut builder,
+        builder,
         bindings,
+        cardinality,
         ..
-    } = insert::<T>();
+    } = insert_into::<T>(rows, columns.is_some());
+
+    let mut builder = builder.into_builder();
 
     builder.push("\nON CONFLICT(");
-    builder.push(T::PRIMARY_KEY.sql);
-    builder.push(")\nDO UPDATE SET\n  ");
+    builder.push(upsert.target().sql());
+    builder.push(")\n");
 
-    let mut separated = builder.separated(",\n  ");
+    let Some(columns) = columns else {
+        builder.push("DO NOTHING");
 
-    for fk in T::FOREIGN_KEYS {
-        separated.push(format!("{} = EXCLUDED.{}", fk.sql, fk.sql));
-    }
+        return Ok(Query::new(
+            query::Operation::Upsert,
+            cardinality,
+            builder,
+            bindings,
+        ));
+    };
 
-    for data in T::DATA_COLUMNS {
-        separated.push(format!("{} = EXCLUDED.{}", data.sql, data.sql));
+    builder.push("DO UPDATE SET\n  ");
+
+    let mut separated = builder.separated(",\n  ");
+
+    for c in columns {
+        separated.push(format!("{} = EXCLUDED.{}", c.sql(), c.sql()));
     }
 
-    for meta in T::TIMESTAMP_COLUMNS {
-        separated.push(format!("{} = EXCLUDED.{}", meta.sql, meta.sql));
+    if let Some(version) = version::<T>() {
+        builder.push(format!(
+            "\nWHERE \"{}\".{} = EXCLUDED.{} - 1",
+            T::TABLE,
+            version.sql,
+            version.sql
+        ));
     }
 
-    Query::new(
+    Ok(Query::new(
         query::Operation::Upsert,
-        query::Cardinality::One,
+        cardinality,
         builder,
         bindings,
-    )
+    ))
 }
 
 /// Generates a `DELETE` query to remove a row from the table based on its primary key.
 ///
 /// SQL: `DELETE FROM .. WHERE ..`
 pub fn delete<T: Bind>() -> Query<T> {
-    delete_by(T::PRIMARY_KEY.as_col())
+    cache::cached("delete", || delete_by(T::PRIMARY_KEY.as_col()))
 }
 
 /// Creates a `DELETE` query to remove rows from the table based on a specific column.
@@ -305,15 +928,125 @@ pub fn delete_by<T: Bind>(c: Column<T>) -> Query<T> {
         query::Operation::Delete,
         query::Cardinality::One,
         builder,
-        Bindings(vec![Column::PrimaryKey(&T::PRIMARY_KEY)]),
+        Bindings::from(vec![Column::PrimaryKey(&T::PRIMARY_KEY)]),
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        runtime::sql::{self, Bindings},
-        Bind, Bindable, Column, DataColumn, ForeignKey, PrimaryKey, Table, TimestampColumn,
+/// Generates an `UPDATE` query which assigns values to the columns of all rows matching
+/// `filter`. If the table has a version column, it is incremented without being checked.
+///
+/// The parameters of the assignments are followed by the parameters of the filter, the values are
+/// bound from the assignments and the filter instead of an entity.
+///
+/// SQL: `UPDATE .. SET .. WHERE ..`
+pub fn update_where<T: Bind>(filter: &Filter<T>, assignments: &Assignments<T>) -> Result<Query<T>> {
+    let mut builder = QueryBuilder::new(format!("UPDATE {} SET\n  ", table::<T>()));
+
+    let mut next = 0;
+    let mut columns = assignments.sql(&mut next)?;
+
+    if let Some(version) = version::<T>() {
+        columns.push(format!("{0} = {0} + 1", version.sql));
+    }
+
+    builder.push(columns.join(",\n  "));
+
+    if let Some(filter) = filter.sql(&mut next)? {
+        builder.push(format!("\nWHERE\n  {filter}"));
+    }
+
+    Ok(Query::new(
+        query::Operation::Update,
+        query::Cardinality::Many,
+        builder,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates a `DELETE` query which removes all rows matching `filter`. The values are bound
+/// from the filter instead of an entity.
+///
+/// SQL: `DELETE FROM .. WHERE ..`
+pub fn delete_where<T: Bind>(filter: &Filter<T>) -> Result<Query<T>> {
+    let mut builder = QueryBuilder::new(format!("DELETE FROM {}", table::<T>()));
+
+    if let Some(filter) = filter.sql(&mut 0)? {
+        builder.push(format!(" WHERE {filter}"));
+    }
+
+    Ok(Query::new(
+        query::Operation::Delete,
+        query::Cardinality::Many,
+        builder,
+        Bindings::empty(),
+    ))
+}
+
+/// Generates the `CREATE TABLE` statement of the table from the definitions of its columns.
+///
+/// SQL: `CREATE TABLE .. (.. PRIMARY KEY, ..)`
+pub fn create_table<T: Ddl>() -> String {
+    let columns: Vec<String> = T::COLUMN_DEFINITIONS.iter().map(definition).collect();
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n)",
+        table::<T>(),
+        columns.join(",\n  ")
+    )
+}
+
+fn definition<T: Table>(d: &ColumnDefinition<T>) -> String {
+    let mut sql = format!("{} {}", d.column.sql(), d.ty);
+
+    if !d.nullable {
+        sql.push_str(" NOT NULL");
+    }
+
+    match d.column {
+        Column::PrimaryKey(_) => {
+            #[cfg(feature = "postgres")]
+            if d.generated {
+                sql.push_str(" GENERATED BY DEFAULT AS IDENTITY");
+            }
+
+            #[cfg(feature = "mysql")]
+            if d.generated {
+                sql.push_str(" AUTO_INCREMENT");
+            }
+
+            // `INTEGER PRIMARY KEY` columns are aliases of the rowid in sqlite, which is generated
+            sql.push_str(" PRIMARY KEY");
+        }
+        _ if d.unique => sql.push_str(" UNIQUE"),
+        _ => {}
+    }
+
+    if let Some(r) = &d.references {
+        sql.push_str(&format!(
+            " REFERENCES {}({})",
+            qualified(r.schema, r.table),
+            r.column
+        ));
+    }
+
+    sql
+}
+
+/// Generates the `DROP TABLE` statement of the table.
+///
+/// SQL: `DROP TABLE ..`
+pub fn drop_table<T: Table>() -> String {
+    format!("DROP TABLE {}", table::<T>())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        filter::{Assignments, Filter},
+        runtime::sql::{self, Bindings},
+        Aggregation, Bind, BindError, Bindable, Column, ColumnDefinition, DataColumn, Ddl, Error,
+        ForeignKey, PrimaryKey, Reference, Table, TimestampColumn, TypedColumn, Upsert,
+        VersionColumn,
     };
 
     #[derive(sqlx::FromRow)]
@@ -352,6 +1085,78 @@ mod tests {
         }
     }
 
+    impl Ddl for TestTable {
+        const COLUMN_DEFINITIONS: &'static [ColumnDefinition<Self>] = &[
+            ColumnDefinition {
+                column: Column::PrimaryKey(&Self::PRIMARY_KEY),
+                ty: "INTEGER",
+                nullable: false,
+                unique: true,
+                generated: true,
+                references: None,
+            },
+            ColumnDefinition {
+                column: Column::ForeignKey(&Self::FOREIGN_KEYS[0]),
+                ty: "INTEGER",
+                nullable: true,
+                unique: false,
+                generated: false,
+                references: Some(Reference::to::<VersionedTable>()),
+            },
+            ColumnDefinition {
+                column: Column::Data(&Self::DATA_COLUMNS[0]),
+                ty: "BOOLEAN",
+                nullable: false,
+                unique: true,
+                generated: false,
+                references: None,
+            },
+        ];
+    }
+
+    #[derive(sqlx::FromRow)]
+    #[allow(unused)]
+    struct VersionedTable {
+        id: i32,
+        data: bool,
+        version: i64,
+    }
+
+    const VERSION: VersionColumn<VersionedTable> = VersionColumn::new("version", "version_sql_col");
+
+    impl Table for VersionedTable {
+        type PrimaryKey = i32;
+
+        const SCHEMA: &'static str = "public";
+        const TABLE: &'static str = "versioned";
+
+        const PRIMARY_KEY: PrimaryKey<Self> = PrimaryKey::new("id", "id_sql_col");
+        const FOREIGN_KEYS: &'static [ForeignKey<Self>] = &[];
+        const DATA_COLUMNS: &'static [DataColumn<Self>] =
+            &[DataColumn::new("data", "data_sql_col")];
+        const TIMESTAMP_COLUMNS: &'static [TimestampColumn<Self>] = &[];
+        const VERSION_COLUMN: Option<VersionColumn<Self>> = Some(VERSION);
+
+        fn pk(&self) -> &Self::PrimaryKey {
+            &self.id
+        }
+
+        fn increment_version(&mut self) {
+            self.version += 1;
+        }
+    }
+
+    impl Bind for VersionedTable {
+        fn bind<'q, Q: Bindable<'q>>(&'q self, c: &'q Column<Self>, query: Q) -> crate::Result<Q> {
+            match c.field() {
+                "id" => Ok(query.dyn_bind(self.id)),
+                "data" => Ok(query.dyn_bind(self.data)),
+                "version" => Ok(query.dyn_bind(self.version)),
+                _ => unimplemented!(),
+            }
+        }
+    }
+
     #[test]
     fn select() {
         let sql::Query {
@@ -365,7 +1170,35 @@ mod tests {
 
         assert_eq!(
             bindings,
-            Bindings(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY),])
+            Bindings::from(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY),])
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "sqlite"))]
+    fn select_locked() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::select_by_locked::<TestTable>(
+            TestTable::PRIMARY_KEY.as_col(),
+            sql::Lock::ForUpdateSkipLocked,
+        );
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  id_sql_col,\n  fk_sql_col,\n  data_sql_col\nFROM\n  \"public\".\"test\"\nWHERE id_sql_col = $1\nFOR UPDATE SKIP LOCKED"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY),])
+        );
+
+        let sql::Query { builder, .. } = sql::select_all_locked::<TestTable>(sql::Lock::ForUpdate);
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  id_sql_col,\n  fk_sql_col,\n  data_sql_col\nFROM\n  \"public\".\"test\"\nFOR UPDATE"
         );
     }
 
@@ -382,7 +1215,7 @@ mod tests {
 
         assert_eq!(
             bindings,
-            Bindings(vec![
+            Bindings::from(vec![
                 Column::PrimaryKey(&TestTable::PRIMARY_KEY),
                 Column::ForeignKey(&TestTable::FOREIGN_KEYS[0]),
                 Column::Data(&TestTable::DATA_COLUMNS[0]),
@@ -390,6 +1223,56 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(not(feature = "mysql"))]
+    fn insert_returning() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::insert_returning::<VersionedTable>([Column::Data(
+            &VersionedTable::DATA_COLUMNS[0],
+        )]);
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"versioned\"\n  (data_sql_col, version_sql_col)\nVALUES\n  ($1, 0)\nRETURNING\n  id_sql_col,\n  data_sql_col,\n  version_sql_col"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![Column::Data(&VersionedTable::DATA_COLUMNS[0])])
+        );
+
+        let sql::Query { builder, .. } = sql::insert_returning::<TestTable>([]);
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"test\"\nDEFAULT VALUES\nRETURNING\n  id_sql_col,\n  fk_sql_col,\n  data_sql_col"
+        );
+    }
+
+    #[test]
+    fn insert_many() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::insert_many::<TestTable>(2);
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"test\"\n  (id_sql_col, fk_sql_col, data_sql_col)\nVALUES\n  ($1, $2, $3),\n  ($4, $5, $6)"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![
+                Column::PrimaryKey(&TestTable::PRIMARY_KEY),
+                Column::ForeignKey(&TestTable::FOREIGN_KEYS[0]),
+                Column::Data(&TestTable::DATA_COLUMNS[0]),
+            ])
+        );
+
+        assert_eq!(sql::insert_many_chunk::<TestTable>(), sql::MAX_BINDINGS / 3);
+    }
+
     #[test]
     fn update() {
         let sql::Query {
@@ -403,7 +1286,7 @@ mod tests {
 
         assert_eq!(
             bindings,
-            Bindings(vec![
+            Bindings::from(vec![
                 Column::PrimaryKey(&TestTable::PRIMARY_KEY),
                 Column::ForeignKey(&TestTable::FOREIGN_KEYS[0]),
                 Column::Data(&TestTable::DATA_COLUMNS[0]),
@@ -411,6 +1294,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_columns() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::update_columns::<TestTable>([Column::Data(&TestTable::DATA_COLUMNS[0])]);
+
+        assert_eq!(
+            builder.sql(),
+            "UPDATE \"public\".\"test\" SET\n  data_sql_col = $2\nWHERE\n  id_sql_col = $1"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![
+                Column::PrimaryKey(&TestTable::PRIMARY_KEY),
+                Column::Data(&TestTable::DATA_COLUMNS[0]),
+            ])
+        );
+    }
+
+    #[test]
+    fn patch_versioned() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::patch::<VersionedTable>([Column::Data(&VersionedTable::DATA_COLUMNS[0])]);
+
+        assert_eq!(
+            builder.sql(),
+            "UPDATE \"public\".\"versioned\" SET\n  data_sql_col = $2,\n  version_sql_col = version_sql_col + 1\nWHERE\n  id_sql_col = $1"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![
+                Column::PrimaryKey(&VersionedTable::PRIMARY_KEY),
+                Column::Data(&VersionedTable::DATA_COLUMNS[0]),
+            ])
+        );
+    }
+
     #[test]
     fn upsert() {
         let sql::Query {
@@ -424,7 +1347,7 @@ mod tests {
 
         assert_eq!(
             bindings,
-            Bindings(vec![
+            Bindings::from(vec![
                 Column::PrimaryKey(&TestTable::PRIMARY_KEY),
                 Column::ForeignKey(&TestTable::FOREIGN_KEYS[0]),
                 Column::Data(&TestTable::DATA_COLUMNS[0]),
@@ -444,7 +1367,335 @@ mod tests {
         );
         assert_eq!(
             bindings,
-            Bindings(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY),])
+            Bindings::from(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY),])
+        );
+    }
+
+    #[test]
+    fn insert_versioned() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::insert::<VersionedTable>();
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"versioned\"\n  (id_sql_col, data_sql_col, version_sql_col)\nVALUES\n  ($1, $2, $3)"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![
+                Column::PrimaryKey(&VersionedTable::PRIMARY_KEY),
+                Column::Data(&VersionedTable::DATA_COLUMNS[0]),
+                Column::Version(&VERSION),
+            ])
+        );
+    }
+
+    #[test]
+    fn update_versioned() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::update::<VersionedTable>();
+
+        assert_eq!(
+            builder.sql(),
+            "UPDATE \"public\".\"versioned\" SET\n  id_sql_col = $1,\n  data_sql_col = $2,\n  version_sql_col = version_sql_col + 1\nWHERE\n  id_sql_col = $1 AND version_sql_col = $3"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![
+                Column::PrimaryKey(&VersionedTable::PRIMARY_KEY),
+                Column::Data(&VersionedTable::DATA_COLUMNS[0]),
+                Column::Version(&VERSION),
+            ])
+        );
+    }
+
+    #[test]
+    fn upsert_with() {
+        let sql::Query { builder, .. } =
+            sql::upsert_with::<TestTable>(2, &Upsert::default().update(["data"])).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"test\"\n  (id_sql_col, fk_sql_col, data_sql_col)\nVALUES\n  ($1, $2, $3),\n  ($4, $5, $6)\nON CONFLICT(id_sql_col)\nDO UPDATE SET\n  data_sql_col = EXCLUDED.data_sql_col"
+        );
+
+        let sql::Query { builder, .. } =
+            sql::upsert_with::<VersionedTable>(1, &Upsert::default().do_nothing()).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"versioned\"\n  (id_sql_col, data_sql_col, version_sql_col)\nVALUES\n  ($1, $2, $3)\nON CONFLICT(id_sql_col)\nDO NOTHING"
+        );
+
+        assert!(sql::upsert_with::<TestTable>(1, &Upsert::default().keep(["unknown"])).is_err());
+    }
+
+    #[test]
+    fn upsert_versioned() {
+        let sql::Query { builder, .. } = sql::upsert::<VersionedTable>();
+
+        assert_eq!(
+            builder.sql(),
+            "INSERT INTO \"public\".\"versioned\"\n  (id_sql_col, data_sql_col, version_sql_col)\nVALUES\n  ($1, $2, $3 + 1)\nON CONFLICT(id_sql_col)\nDO UPDATE SET\n  data_sql_col = EXCLUDED.data_sql_col,\n  version_sql_col = EXCLUDED.version_sql_col\nWHERE \"versioned\".version_sql_col = EXCLUDED.version_sql_col - 1"
+        );
+    }
+
+    #[test]
+    fn update_where() {
+        let sql::Query { builder, .. } = sql::update_where::<VersionedTable>(
+            &Filter::new().ne("id", 1),
+            &Assignments::new().set("data", false),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "UPDATE \"public\".\"versioned\" SET\n  data_sql_col = $1,\n  version_sql_col = version_sql_col + 1\nWHERE\n  id_sql_col <> $2"
+        );
+
+        let filter = Filter::new().eq("fk", 1).is_null("data");
+
+        let sql::Query { builder, .. } = sql::update_where::<TestTable>(
+            &filter.or(Filter::new().is_in("id", [1, 2])),
+            &Assignments::new().set("data", true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "UPDATE \"public\".\"test\" SET\n  data_sql_col = $1\nWHERE\n  (fk_sql_col = $2 AND data_sql_col IS NULL OR id_sql_col IN ($3, $4))"
+        );
+
+        let assignments = Assignments::<TestTable>::new().set("id", 1);
+        assert!(sql::update_where(&Filter::all(), &assignments).is_err());
+    }
+
+    #[test]
+    fn delete_where() {
+        let sql::Query { builder, .. } =
+            sql::delete_where::<TestTable>(&Filter::new().ge("fk", 1).lt("fk", 5)).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "DELETE FROM \"public\".\"test\" WHERE fk_sql_col >= $1 AND fk_sql_col < $2"
+        );
+
+        let sql::Query { builder, .. } = sql::delete_where::<TestTable>(&Filter::all()).unwrap();
+        assert_eq!(builder.sql(), "DELETE FROM \"public\".\"test\"");
+
+        assert!(matches!(
+            sql::delete_where::<TestTable>(&Filter::new()),
+            Err(Error::EmptyFilter)
+        ));
+        assert!(matches!(
+            sql::delete_where::<TestTable>(&Filter::new().or(Filter::new().eq("fk", 1))),
+            Err(Error::EmptyFilter)
+        ));
+        assert!(sql::delete_where::<TestTable>(&Filter::new().eq("unknown", 1)).is_err());
+    }
+
+    #[test]
+    fn exists() {
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::exists_by::<TestTable>(TestTable::PRIMARY_KEY.as_col());
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT 1 FROM \"public\".\"test\" WHERE id_sql_col = $1 LIMIT 1"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY)])
+        );
+
+        let sql::Query { builder, .. } = sql::exists_where::<TestTable>(&Filter::new()).unwrap();
+
+        assert_eq!(builder.sql(), "SELECT 1 FROM \"public\".\"test\" LIMIT 1");
+
+        let sql::Query { builder, .. } =
+            sql::exists_where::<TestTable>(&Filter::new().eq("fk", 1).is_null("data")).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT 1 FROM \"public\".\"test\" WHERE fk_sql_col = $1 AND data_sql_col IS NULL LIMIT 1"
+        );
+    }
+
+    #[test]
+    fn aggregate() {
+        let id = TypedColumn::<TestTable, i32>::new(TestTable::PRIMARY_KEY.as_col());
+
+        let sql::Query { builder, .. } =
+            sql::aggregate::<TestTable, _>(&Aggregation::count(), &Filter::new()).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  count(*)\nFROM\n  \"public\".\"test\"\n"
+        );
+
+        let sql::Query { builder, .. } =
+            sql::aggregate(&Aggregation::max(id.clone()), &Filter::new().eq("fk", 1)).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  max(id_sql_col)\nFROM\n  \"public\".\"test\"\nWHERE\n  fk_sql_col = $1\n"
+        );
+
+        #[cfg(feature = "postgres")]
+        assert_eq!(
+            Aggregation::sum(id.clone()).sql(),
+            "CAST(sum(id_sql_col) AS BIGINT)"
+        );
+
+        #[cfg(feature = "postgres")]
+        assert_eq!(
+            Aggregation::avg(id).sql(),
+            "CAST(avg(id_sql_col) AS DOUBLE PRECISION)"
+        );
+    }
+
+    #[test]
+    fn group_by() {
+        let fk = TestTable::FOREIGN_KEYS[0].as_col();
+        let data = TestTable::DATA_COLUMNS[0].as_col();
+
+        let sql::Query { builder, .. } = sql::group_by::<TestTable, _>(
+            &[&fk, &data],
+            &Aggregation::count(),
+            &Filter::new().gt("id", 1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  fk_sql_col, data_sql_col,\n  count(*)\nFROM\n  \"public\".\"test\"\nWHERE\n  id_sql_col > $1\nGROUP BY fk_sql_col, data_sql_col\nORDER BY fk_sql_col, data_sql_col\n"
+        );
+    }
+
+    #[test]
+    fn project() {
+        let columns = [
+            TestTable::PRIMARY_KEY.as_col(),
+            TestTable::DATA_COLUMNS[0].as_col(),
+        ];
+
+        let sql::Query {
+            builder, bindings, ..
+        } = sql::project_by::<TestTable>(&columns, TestTable::PRIMARY_KEY.as_col());
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  id_sql_col,\n  data_sql_col\nFROM\n  \"public\".\"test\"\nWHERE id_sql_col = $1"
+        );
+
+        assert_eq!(
+            bindings,
+            Bindings::from(vec![Column::PrimaryKey(&TestTable::PRIMARY_KEY)])
+        );
+
+        let sql::Query { builder, .. } =
+            sql::project::<TestTable>(&columns, &Filter::new().eq("fk", 1)).unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  id_sql_col,\n  data_sql_col\nFROM\n  \"public\".\"test\"\nWHERE\n  fk_sql_col = $1\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn search() {
+        let sql::Query { builder, .. } =
+            sql::search::<TestTable>(TestTable::DATA_COLUMNS, "english");
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  \"public\".\"test\".id_sql_col,\n  \"public\".\"test\".fk_sql_col,\n  \"public\".\"test\".data_sql_col\nFROM\n  \"public\".\"test\"\nWHERE to_tsvector('english'::regconfig, coalesce(\"public\".\"test\".data_sql_col, '')) @@ websearch_to_tsquery('english'::regconfig, $1)\nORDER BY ts_rank(to_tsvector('english'::regconfig, coalesce(\"public\".\"test\".data_sql_col, '')), websearch_to_tsquery('english'::regconfig, $1)) DESC"
+        );
+    }
+
+    #[test]
+    fn join() {
+        let sql::Query { builder, .. } = sql::join::<TestTable, VersionedTable>(
+            sql::Join::Left,
+            TestTable::FOREIGN_KEYS[0].as_col(),
+            VersionedTable::PRIMARY_KEY.as_col(),
+            &Filter::new().eq("data", true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.sql(),
+            "SELECT\n  \"t0\".id_sql_col AS \"t0.id_sql_col\",\n  \"t0\".fk_sql_col AS \"t0.fk_sql_col\",\n  \"t0\".data_sql_col AS \"t0.data_sql_col\",\n  \"t1\".id_sql_col AS \"t1.id_sql_col\",\n  \"t1\".data_sql_col AS \"t1.data_sql_col\",\n  \"t1\".version_sql_col AS \"t1.version_sql_col\"\nFROM\n  \"public\".\"test\" AS \"t0\"\nLEFT JOIN \"public\".\"versioned\" AS \"t1\" ON \"t0\".fk_sql_col = \"t1\".id_sql_col\nWHERE\n  \"t0\".data_sql_col = $1\n"
+        );
+
+        assert!(sql::join::<TestTable, VersionedTable>(
+            sql::Join::Inner,
+            TestTable::FOREIGN_KEYS[0].as_col(),
+            VersionedTable::PRIMARY_KEY.as_col(),
+            &Filter::new().eq("version", 1),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn interpolate() {
+        assert_eq!(
+            sql::interpolate::<TestTable>("SELECT * FROM {table} WHERE {fk} = $1 AND {data}")
+                .unwrap(),
+            "SELECT * FROM \"public\".\"test\" WHERE fk_sql_col = $1 AND data_sql_col"
+        );
+
+        assert_eq!(
+            sql::interpolate::<TestTable>("SELECT '{{1,2}}'::int[] FROM {table}").unwrap(),
+            "SELECT '{1,2}'::int[] FROM \"public\".\"test\""
+        );
+
+        for invalid in ["{unknown}", "{fk", "fk}", "{{fk}"] {
+            assert!(matches!(
+                sql::interpolate::<TestTable>(invalid),
+                Err(Error::Bind(BindError::Identifier(_)))
+            ));
+        }
+    }
+
+    #[test]
+    fn cached() {
+        let first = sql::select::<TestTable>();
+        let second = sql::select::<TestTable>();
+
+        assert!(std::ptr::eq(first.sql(), second.sql()));
+        assert_eq!(first.bindings(), second.bindings());
+
+        let generated = sql::select_by::<TestTable>(TestTable::PRIMARY_KEY.as_col());
+
+        assert_eq!(first.sql(), generated.sql());
+        assert_eq!(first.bindings(), generated.bindings());
+
+        // statements are cached per table
+        assert_ne!(
+            sql::update::<TestTable>().sql(),
+            sql::update::<VersionedTable>().sql()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn ddl() {
+        assert_eq!(
+            TestTable::create_table_sql(),
+            "CREATE TABLE \"public\".\"test\" (\n  id_sql_col INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,\n  fk_sql_col INTEGER REFERENCES \"public\".\"versioned\"(id_sql_col),\n  data_sql_col BOOLEAN NOT NULL UNIQUE\n)"
+        );
+
+        assert_eq!(
+            TestTable::drop_table_sql(),
+            "DROP TABLE \"public\".\"test\""
         );
     }
 }
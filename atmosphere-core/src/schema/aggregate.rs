@@ -0,0 +1,338 @@
+use std::marker::PhantomData;
+
+use crate::{
+    filter::Filter,
+    hooks::{self, HookInput, HookStage, Hooks},
+    query::QueryError,
+    Bind, Column, Error, Result, Table, TypedColumn,
+};
+
+use async_trait::async_trait;
+use sqlx::{database::HasArguments, Database, Decode, Executor, IntoArguments, Row, Type};
+
+#[cfg(not(feature = "mysql"))]
+const INTEGER: &str = "BIGINT";
+
+#[cfg(feature = "mysql")]
+const INTEGER: &str = "SIGNED";
+
+#[cfg(feature = "postgres")]
+const FLOAT: &str = "DOUBLE PRECISION";
+
+#[cfg(feature = "mysql")]
+const FLOAT: &str = "DOUBLE";
+
+#[cfg(feature = "sqlite")]
+const FLOAT: &str = "REAL";
+
+/// Rust types of columns which can be summed up.
+///
+/// As databases widen the result of `sum` differently (e.g. postgres sums `BIGINT` columns as
+/// `NUMERIC`), sums are cast to `SQL`, the sql type of `Sum`.
+pub trait Summable {
+    /// The rust type of the sum
+    type Sum;
+
+    /// The sql type of the sum
+    const SQL: &'static str;
+}
+
+macro_rules! summable {
+    ($sum:ty, $sql:expr, $($ty:ty),+) => {
+        $(
+            impl Summable for $ty {
+                type Sum = $sum;
+                const SQL: &'static str = $sql;
+            }
+
+            impl Summable for Option<$ty> {
+                type Sum = $sum;
+                const SQL: &'static str = $sql;
+            }
+        )+
+    };
+}
+
+summable!(i64, INTEGER, i8, i16, i32, i64);
+summable!(f64, FLOAT, f32, f64);
+
+/// An aggregate function over the rows of table `T`, resulting in a value of type `A`.
+///
+/// The result types follow the types of the aggregated columns: `min` and `max` result in values
+/// of the column type, `sum` in the type of the sum (see [`Summable`]) and `avg` in `f64`. Except
+/// for `count`, aggregates result in `None` if there are no rows.
+pub struct Aggregation<T: Table, A> {
+    sql: String,
+    result: PhantomData<fn() -> (T, A)>,
+}
+
+impl<T: Table, A> Aggregation<T, A> {
+    fn new(sql: String) -> Self {
+        Self {
+            sql,
+            result: PhantomData,
+        }
+    }
+
+    /// The sql expression of the aggregate
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+impl<T: Table> Aggregation<T, i64> {
+    /// Counts the rows
+    pub fn count() -> Self {
+        Self::new("count(*)".to_string())
+    }
+}
+
+impl<T: Table, S> Aggregation<T, Option<S>> {
+    /// Sums up the values of `column`
+    pub fn sum<V: Summable<Sum = S>>(column: TypedColumn<T, V>) -> Self {
+        let column = column.column().sql();
+        Self::new(format!("CAST(sum({column}) AS {})", V::SQL))
+    }
+}
+
+impl<T: Table> Aggregation<T, Option<f64>> {
+    /// Averages the values of `column`
+    pub fn avg<V: Summable>(column: TypedColumn<T, V>) -> Self {
+        let column = column.column().sql();
+        Self::new(format!("CAST(avg({column}) AS {FLOAT})"))
+    }
+}
+
+impl<T: Table, V> Aggregation<T, Option<V>> {
+    /// The smallest value of `column`
+    pub fn min(column: TypedColumn<T, V>) -> Self {
+        Self::new(format!("min({})", column.column().sql()))
+    }
+
+    /// The largest value of `column`
+    pub fn max(column: TypedColumn<T, V>) -> Self {
+        Self::new(format!("max({})", column.column().sql()))
+    }
+}
+
+/// The columns of table `T` rows are grouped by, resulting in keys of type `Key`.
+///
+/// Implemented for typed columns and tuples of up to four typed columns, whose keys are the rust
+/// types of the columns (e.g. `(User::col().country, User::col().active)` results in
+/// `(String, bool)`).
+pub trait GroupKey<T: Table> {
+    /// The rust type of a key
+    type Key;
+
+    /// The grouped columns
+    fn columns(&self) -> Vec<&Column<T>>;
+
+    /// Decodes the key of a group from the leading columns of `row`.
+    fn decode(
+        row: &<crate::Driver as Database>::Row,
+    ) -> std::result::Result<Self::Key, sqlx::Error>;
+}
+
+impl<T: Table, V> GroupKey<T> for TypedColumn<T, V>
+where
+    V: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver>,
+{
+    type Key = V;
+
+    fn columns(&self) -> Vec<&Column<T>> {
+        vec![self.column()]
+    }
+
+    fn decode(row: &<crate::Driver as Database>::Row) -> std::result::Result<V, sqlx::Error> {
+        row.try_get(0)
+    }
+}
+
+macro_rules! group_key {
+    ($($value:ident: $index:tt),+) => {
+        impl<T: Table, $($value),+> GroupKey<T> for ($(TypedColumn<T, $value>,)+)
+        where
+            $($value: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver>),+
+        {
+            type Key = ($($value,)+);
+
+            fn columns(&self) -> Vec<&Column<T>> {
+                vec![$(self.$index.column()),+]
+            }
+
+            fn decode(
+                row: &<crate::Driver as Database>::Row,
+            ) -> std::result::Result<Self::Key, sqlx::Error> {
+                Ok(($(row.try_get::<$value, _>($index)?,)+))
+            }
+        }
+    };
+}
+
+group_key!(A: 0);
+group_key!(A: 0, B: 1);
+group_key!(A: 0, B: 1, C: 2);
+group_key!(A: 0, B: 1, C: 2, D: 3);
+
+/// Aggregate queries over the rows of a table.
+///
+/// Aggregates are computed by the database over all rows matching a filter (`Filter::new()`
+/// matches all rows) and return typed results derived from the aggregated columns:
+///
+/// ```ignore
+/// let users: i64 = User::count(&Filter::new(), &pool).await?;
+/// let latest: Option<DateTime<Utc>> =
+///     User::maximum(User::col().created_at, &Filter::new(), &pool).await?;
+/// ```
+///
+/// Hooks are executed before binding and before execution, without any row as input.
+#[async_trait]
+pub trait Aggregate: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
+    /// Computes an aggregate over the rows matching `filter`.
+    async fn aggregate<'e, E, A>(
+        aggregation: &Aggregation<Self, A>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<A>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        A: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver> + Send + Unpin,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::aggregate(aggregation, filter)?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        sqlx::query_scalar_with(query.sql(), args)
+            .persistent(false)
+            .fetch_one(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
+            .map_err(Error::Query)
+    }
+
+    /// Groups the rows matching `filter` by the columns of `key` and computes an aggregate per
+    /// group. The groups are ordered by their keys.
+    ///
+    /// ```ignore
+    /// let users: Vec<(String, i64)> =
+    ///     User::group_by(User::col().country, &Aggregation::count(), &Filter::new(), &pool).await?;
+    /// ```
+    async fn group_by<'e, E, K, A>(
+        key: K,
+        aggregation: &Aggregation<Self, A>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Vec<(K::Key, A)>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        K: GroupKey<Self> + Send,
+        K::Key: Send,
+        A: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver> + Send + Unpin,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let columns = key.columns();
+        let query = crate::runtime::sql::group_by(&columns, aggregation, filter)?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let rows = sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
+            .map_err(Error::Query)?;
+
+        rows.iter()
+            .map(|row| Ok((K::decode(row)?, row.try_get(columns.len())?)))
+            .collect::<std::result::Result<_, sqlx::Error>>()
+            .map_err(|e| QueryError::from(e).within::<Self>(query.op))
+            .map_err(Error::Query)
+    }
+
+    /// Counts the rows matching `filter`.
+    async fn count<'e, E>(filter: &Filter<Self>, executor: E) -> Result<i64>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Self::aggregate(&Aggregation::count(), filter, executor).await
+    }
+
+    /// Sums up the values of `column` of the rows matching `filter`.
+    async fn sum<'e, E, V>(
+        column: TypedColumn<Self, V>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Option<V::Sum>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        V: Summable,
+        V::Sum: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver> + Send + Unpin,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Self::aggregate(&Aggregation::sum(column), filter, executor).await
+    }
+
+    /// Averages the values of `column` of the rows matching `filter`.
+    async fn avg<'e, E, V>(
+        column: TypedColumn<Self, V>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Option<f64>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        V: Summable,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Self::aggregate(&Aggregation::avg(column), filter, executor).await
+    }
+
+    /// The smallest value of `column` of the rows matching `filter`. (Not named `min`, which would
+    /// be ambiguous with `Ord::min` for entities implementing `Ord`.)
+    async fn minimum<'e, E, V>(
+        column: TypedColumn<Self, V>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Option<V>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        V: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver> + Send + Unpin,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Self::aggregate(&Aggregation::min(column), filter, executor).await
+    }
+
+    /// The largest value of `column` of the rows matching `filter`.
+    async fn maximum<'e, E, V>(
+        column: TypedColumn<Self, V>,
+        filter: &Filter<Self>,
+        executor: E,
+    ) -> Result<Option<V>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        V: for<'r> Decode<'r, crate::Driver> + Type<crate::Driver> + Send + Unpin,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Self::aggregate(&Aggregation::max(column), filter, executor).await
+    }
+}
+
+impl<T> Aggregate for T where T: Table + Bind + Hooks + Send + Sync + Unpin + 'static {}
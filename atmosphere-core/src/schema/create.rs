@@ -6,7 +6,7 @@ use crate::{
 };
 
 use async_trait::async_trait;
-use sqlx::{database::HasArguments, Executor, IntoArguments};
+use sqlx::{database::HasArguments, Acquire, Database, Executor, IntoArguments};
 
 /// Trait for creating rows in a database.
 ///
@@ -27,6 +27,20 @@ pub trait Create: Table + Bind + Hooks + Sync + 'static {
         E: Executor<'e, Database = crate::Driver>,
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
+
+    /// Creates many rows using multi-row `INSERT` statements. The rows are split into as few
+    /// statements as the bind parameter limit of the driver allows, which are executed within a
+    /// transaction (or savepoint) begun on `conn`, so that either all or none of the rows are
+    /// created. Hooks run at the pre-binding stage once per row and at the execution stages once
+    /// per statement.
+    async fn create_many<'a, A>(
+        entities: &mut [Self],
+        conn: A,
+    ) -> Result<<crate::Driver as sqlx::Database>::QueryResult>
+    where
+        A: Acquire<'a, Database = crate::Driver> + Send,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
 }
 
 #[async_trait]
@@ -54,10 +68,9 @@ where
         }
 
         let res = builder
-            .persistent(false)
             .execute(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -69,4 +82,67 @@ where
 
         res
     }
+
+    async fn create_many<'a, A>(
+        entities: &mut [Self],
+        conn: A,
+    ) -> Result<<crate::Driver as sqlx::Database>::QueryResult>
+    where
+        A: Acquire<'a, Database = crate::Driver> + Send,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let mut total = <crate::Driver as Database>::QueryResult::default();
+
+        if entities.is_empty() {
+            return Ok(total);
+        }
+
+        let mut tx = conn
+            .begin()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        for chunk in entities.chunks_mut(crate::runtime::sql::insert_many_chunk::<T>()) {
+            let query = crate::runtime::sql::insert_many::<T>(chunk.len());
+
+            for entity in chunk.iter_mut() {
+                hooks::execute(HookStage::PreBind, &query, HookInput::Row(entity)).await?;
+            }
+
+            let mut builder = sqlx::query(query.sql());
+
+            for entity in chunk.iter() {
+                for c in query.bindings().columns() {
+                    builder = entity.bind(c, builder).unwrap();
+                }
+            }
+
+            hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+            let res = builder
+                .persistent(false)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| QueryError::from(e).within::<T>(query.op))
+                .map_err(Error::Query);
+
+            hooks::execute(
+                HookStage::PostExec,
+                &query,
+                QueryResult::Execution(&res).into(),
+            )
+            .await?;
+
+            total.extend(Some(res?));
+        }
+
+        tx.commit()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        Ok(total)
+    }
 }
@@ -0,0 +1,161 @@
+use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+use crate::{Column, Table};
+
+/// Rust types of columns whose sql types are known.
+///
+/// Used by `#[derive(Schema)]` to derive the column types of `CREATE TABLE` statements (see
+/// [`Ddl`]) in the dialect of the driver. Columns of optional types are nullable, all other
+/// columns are `NOT NULL`. Implement this trait for custom column types (e.g. enums deriving
+/// `sqlx::Type`) to use them in generated statements.
+pub trait SqlType {
+    /// The sql type of the column
+    const SQL: &'static str;
+
+    /// Whether the column is nullable
+    const NULLABLE: bool = false;
+}
+
+impl<T: SqlType> SqlType for Option<T> {
+    const SQL: &'static str = T::SQL;
+    const NULLABLE: bool = true;
+}
+
+macro_rules! sql_type {
+    ($($ty:ty => $sql:expr),+ $(,)?) => {
+        $(
+            impl SqlType for $ty {
+                const SQL: &'static str = $sql;
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "postgres")]
+sql_type!(
+    bool => "BOOLEAN",
+    i16 => "SMALLINT",
+    i32 => "INTEGER",
+    i64 => "BIGINT",
+    f32 => "REAL",
+    f64 => "DOUBLE PRECISION",
+    String => "TEXT",
+    Vec<u8> => "BYTEA",
+    DateTime<Utc> => "TIMESTAMPTZ",
+    NaiveDateTime => "TIMESTAMP",
+    NaiveDate => "DATE",
+    NaiveTime => "TIME",
+);
+
+// `TEXT` columns can not be keys or unique in mysql without a prefix length
+#[cfg(feature = "mysql")]
+sql_type!(
+    bool => "BOOLEAN",
+    i8 => "TINYINT",
+    i16 => "SMALLINT",
+    i32 => "INT",
+    i64 => "BIGINT",
+    u8 => "TINYINT UNSIGNED",
+    u16 => "SMALLINT UNSIGNED",
+    u32 => "INT UNSIGNED",
+    u64 => "BIGINT UNSIGNED",
+    f32 => "FLOAT",
+    f64 => "DOUBLE",
+    String => "VARCHAR(255)",
+    Vec<u8> => "BLOB",
+    DateTime<Utc> => "TIMESTAMP",
+    NaiveDateTime => "DATETIME",
+    NaiveDate => "DATE",
+    NaiveTime => "TIME",
+);
+
+#[cfg(feature = "sqlite")]
+sql_type!(
+    bool => "BOOLEAN",
+    i8 => "INTEGER",
+    i16 => "INTEGER",
+    i32 => "INTEGER",
+    i64 => "INTEGER",
+    u8 => "INTEGER",
+    u16 => "INTEGER",
+    u32 => "INTEGER",
+    f32 => "REAL",
+    f64 => "REAL",
+    String => "TEXT",
+    Vec<u8> => "BLOB",
+    DateTime<Utc> => "DATETIME",
+    NaiveDateTime => "DATETIME",
+    NaiveDate => "DATE",
+    NaiveTime => "TIME",
+);
+
+/// The column referenced by a foreign key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reference {
+    /// The schema of the referenced table
+    pub schema: &'static str,
+    /// The referenced table
+    pub table: &'static str,
+    /// The referenced column, the primary key of the table
+    pub column: &'static str,
+}
+
+impl Reference {
+    /// References the primary key of table `T`
+    pub const fn to<T: Table>() -> Self {
+        Self {
+            schema: T::SCHEMA,
+            table: T::TABLE,
+            column: T::PRIMARY_KEY.sql,
+        }
+    }
+}
+
+/// The definition of a column of table `T` in its `CREATE TABLE` statement.
+#[derive(Debug)]
+pub struct ColumnDefinition<T: Table> {
+    /// The defined column
+    pub column: Column<T>,
+    /// The sql type of the column
+    pub ty: &'static str,
+    /// Whether the column is nullable
+    pub nullable: bool,
+    /// Whether the values of the column are unique
+    pub unique: bool,
+    /// Whether the values of the column are generated by the database
+    pub generated: bool,
+    /// The column referenced by a foreign key
+    pub references: Option<Reference>,
+}
+
+/// Data definition statements of a table.
+///
+/// Implemented by `#[derive(Schema)]`, which derives the definitions of the columns from the
+/// struct, in the order primary key, foreign keys, data columns, version column and timestamp
+/// columns:
+///
+/// - the sql types are derived from the field types (see [`SqlType`]), fields of optional types
+///   are nullable
+/// - the primary key is generated by the database if marked as `#[sql(pk, generated)]`
+/// - `#[sql(unique)]` columns are `UNIQUE`
+/// - `#[sql(fk -> Table)]` columns reference the primary key of `Table`
+///
+/// ```ignore
+/// sqlx::query(&Forest::create_table_sql()).execute(&pool).await?;
+/// ```
+///
+/// The implementation only applies if all field types implement [`SqlType`].
+pub trait Ddl: Table {
+    /// The definitions of the columns
+    const COLUMN_DEFINITIONS: &'static [ColumnDefinition<Self>];
+
+    /// Generates the `CREATE TABLE` statement of the table.
+    fn create_table_sql() -> String {
+        crate::runtime::sql::create_table::<Self>()
+    }
+
+    /// Generates the `DROP TABLE` statement of the table.
+    fn drop_table_sql() -> String {
+        crate::runtime::sql::drop_table::<Self>()
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    filter::Filter,
     hooks::{self, Hooks},
     query::{QueryError, QueryResult},
     schema::Table,
@@ -38,6 +39,17 @@ pub trait Delete: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         E: Executor<'e, Database = crate::Driver>,
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
+
+    /// Deletes all rows matching `filter` in a single statement and returns the number of deleted
+    /// rows. Hooks are executed once for the statement, without any row as input.
+    ///
+    /// Filters without conditions are refused with `Error::EmptyFilter` unless they were created
+    /// using `Filter::all`.
+    async fn delete_where<'e, E>(filter: &Filter<Self>, executor: E) -> Result<u64>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
 }
 
 #[async_trait]
@@ -72,10 +84,9 @@ where
         hooks::execute(hooks::HookStage::PreExec, &query, hooks::HookInput::None).await?;
 
         let res = sql
-            .persistent(false)
             .execute(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -114,10 +125,9 @@ where
 
         let res = sqlx::query(query.sql())
             .bind(pk)
-            .persistent(false)
             .execute(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -129,4 +139,36 @@ where
 
         res
     }
+
+    async fn delete_where<'e, E>(filter: &Filter<Self>, executor: E) -> Result<u64>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::delete_where::<T>(filter)?;
+
+        hooks::execute(hooks::HookStage::PreBind, &query, hooks::HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(hooks::HookStage::PreExec, &query, hooks::HookInput::None).await?;
+
+        let res = sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .execute(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::Execution(&res).into(),
+        )
+        .await?;
+
+        res.map(|res| res.rows_affected())
+    }
 }
@@ -0,0 +1,65 @@
+use crate::{hooks::Hooks, Bind, Bindable, Column, Result};
+
+#[cfg(not(feature = "mysql"))]
+use crate::{
+    hooks::{self, HookInput, HookStage},
+    query::{QueryError, QueryResult},
+    Error,
+};
+
+use async_trait::async_trait;
+#[cfg(not(feature = "mysql"))]
+use sqlx::{database::HasArguments, Executor, IntoArguments};
+
+/// The values for inserting a new row.
+///
+/// Insertable structs hold the columns of their table which are not managed by the database,
+/// i.e. all columns except for generated primary keys (`#[sql(pk, generated)]`), timestamps and
+/// the version. They are generated by `#[derive(Schema)]` if the `#[insertable]` attribute is
+/// used on the table (e.g. `NewUser` for `User`).
+#[async_trait]
+pub trait Insertable: Send + Sync {
+    /// The table this struct is inserted into.
+    type Table: Bind + Hooks + Send + Sync + Unpin;
+
+    /// The columns provided by this struct.
+    fn columns() -> Vec<Column<Self::Table>>;
+
+    /// Binds the value of a single column to a given query.
+    fn bind<'q, Q: Bindable<'q>>(&'q self, c: &'q Column<Self::Table>, query: Q) -> Result<Q>;
+
+    /// Inserts a new row and returns it, including all values assigned by the database. Columns
+    /// not provided by this struct are set to their defaults, except for the version column,
+    /// which is initialized with `0`.
+    ///
+    /// Not available with the `mysql` driver, as MySQL does not support `RETURNING`.
+    #[cfg(not(feature = "mysql"))]
+    async fn insert<'e, E>(&self, executor: E) -> Result<Self::Table>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::insert_returning::<Self::Table>(Self::columns());
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut sql = sqlx::query_as(query.sql());
+
+        for c in query.bindings().columns() {
+            sql = self.bind(c, sql).unwrap();
+        }
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let res = sql
+            .fetch_one(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self::Table>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(HookStage::PostExec, &query, QueryResult::One(&res).into()).await?;
+
+        res
+    }
+}
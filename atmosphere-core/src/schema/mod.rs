@@ -7,17 +7,35 @@
 
 use sqlx::{Database, Encode, FromRow, Type};
 
+mod aggregate;
 mod create;
+mod ddl;
 mod delete;
+mod insertable;
+mod patch;
+mod projection;
+mod raw;
 mod read;
+mod search;
+mod tracked;
 mod update;
 
+pub use aggregate::{Aggregate, Aggregation, GroupKey, Summable};
 pub use create::Create;
+pub use ddl::{ColumnDefinition, Ddl, Reference, SqlType};
 pub use delete::Delete;
+pub use insertable::Insertable;
+pub use patch::Patch;
+pub use projection::Projection;
+pub use raw::{Raw, RawQuery};
 pub use read::Read;
-pub use update::Update;
+pub use search::Search;
+pub use tracked::Tracked;
+pub use update::{Update, Upsert};
 
-pub use self::column::{Column, DataColumn, ForeignKey, PrimaryKey, TimestampColumn};
+pub use self::column::{
+    Column, DataColumn, ForeignKey, PrimaryKey, TimestampColumn, TypedColumn, VersionColumn,
+};
 
 /// SQL Table Definition
 ///
@@ -46,9 +64,15 @@ where
     const DATA_COLUMNS: &'static [DataColumn<Self>];
     /// An array of timestamp columns.
     const TIMESTAMP_COLUMNS: &'static [TimestampColumn<Self>];
+    /// The version column, if the table uses optimistic concurrency control.
+    const VERSION_COLUMN: Option<VersionColumn<Self>> = None;
 
     /// Returns a reference to the primary key of the table instance.
     fn pk(&self) -> &Self::PrimaryKey;
+
+    /// Increments the version of the table instance after it was written. Tables declaring a
+    /// `VERSION_COLUMN` must implement this accordingly.
+    fn increment_version(&mut self) {}
 }
 
 /// Trait representing an Entity that maps to a database table.
@@ -81,6 +105,8 @@ pub mod column {
         Data(&'static DataColumn<T>),
         /// A timestamp column
         Timestamp(&'static TimestampColumn<T>),
+        /// A version column
+        Version(&'static VersionColumn<T>),
     }
 
     impl<T: Table> Clone for Column<T> {
@@ -90,6 +116,7 @@ pub mod column {
                 Self::ForeignKey(fk) => Self::ForeignKey(*fk),
                 Self::Data(data) => Self::Data(*data),
                 Self::Timestamp(ts) => Self::Timestamp(*ts),
+                Self::Version(v) => Self::Version(*v),
             }
         }
     }
@@ -101,7 +128,26 @@ pub mod column {
                 Self::ForeignKey(fk) => fk.field,
                 Self::Data(data) => data.field,
                 Self::Timestamp(ts) => ts.field,
+                Self::Version(v) => v.field,
+            }
+        }
+
+        /// Finds the column of `field`
+        pub(crate) fn by_field(field: &str) -> Option<Self> {
+            if T::PRIMARY_KEY.field == field {
+                return Some(Self::PrimaryKey(&T::PRIMARY_KEY));
             }
+
+            if let Some(version) = T::VERSION_COLUMN.as_ref().filter(|v| v.field == field) {
+                return Some(Self::Version(version));
+            }
+
+            T::FOREIGN_KEYS
+                .iter()
+                .map(Self::ForeignKey)
+                .chain(T::DATA_COLUMNS.iter().map(Self::Data))
+                .chain(T::TIMESTAMP_COLUMNS.iter().map(Self::Timestamp))
+                .find(|c| c.field() == field)
         }
 
         pub const fn sql(&self) -> &'static str {
@@ -110,6 +156,7 @@ pub mod column {
                 Self::ForeignKey(fk) => fk.sql,
                 Self::Data(data) => data.sql,
                 Self::Timestamp(ts) => ts.sql,
+                Self::Version(v) => v.sql,
             }
         }
     }
@@ -119,7 +166,7 @@ pub mod column {
     pub struct PrimaryKey<T: Table> {
         pub field: &'static str,
         pub sql: &'static str,
-        table: PhantomData<T>,
+        table: PhantomData<fn() -> T>,
     }
 
     impl<T: Table> PrimaryKey<T> {
@@ -153,7 +200,7 @@ pub mod column {
         pub field: &'static str,
         /// The associated sql column name
         pub sql: &'static str,
-        table: PhantomData<T>,
+        table: PhantomData<fn() -> T>,
     }
 
     impl<T: Table> ForeignKey<T> {
@@ -198,7 +245,7 @@ pub mod column {
         pub field: &'static str,
         /// The associated sql column name
         pub sql: &'static str,
-        table: PhantomData<T>,
+        table: PhantomData<fn() -> T>,
     }
 
     impl<T: Table> DataColumn<T> {
@@ -242,7 +289,7 @@ pub mod column {
         pub field: &'static str,
         /// The associated sql column name
         pub sql: &'static str,
-        table: PhantomData<T>,
+        table: PhantomData<fn() -> T>,
     }
 
     impl<T: Table> TimestampColumn<T> {
@@ -256,6 +303,12 @@ pub mod column {
         }
     }
 
+    impl<T: Table> TimestampColumn<T> {
+        pub const fn as_col(&'static self) -> Column<T> {
+            Column::Timestamp(self)
+        }
+    }
+
     impl<T: Table> Clone for TimestampColumn<T> {
         fn clone(&self) -> Self {
             Self {
@@ -266,4 +319,85 @@ pub mod column {
             }
         }
     }
+
+    /// Describes a version column used for optimistic concurrency control.
+    ///
+    /// The version is incremented on every update and compared against the version of the
+    /// updated instance, so that concurrent modifications are detected instead of silently
+    /// overwritten.
+    #[derive(Copy, Debug, PartialEq, Eq)]
+    pub struct VersionColumn<T: Table> {
+        /// The rust field name of the model
+        pub field: &'static str,
+        /// The associated sql column name
+        pub sql: &'static str,
+        table: PhantomData<fn() -> T>,
+    }
+
+    impl<T: Table> VersionColumn<T> {
+        pub const fn new(field: &'static str, sql: &'static str) -> Self {
+            Self {
+                field,
+                sql,
+                table: PhantomData,
+            }
+        }
+
+        pub const fn as_col(&'static self) -> Column<T> {
+            Column::Version(self)
+        }
+    }
+
+    impl<T: Table> Clone for VersionColumn<T> {
+        fn clone(&self) -> Self {
+            Self {
+                field: self.field,
+                sql: self.sql,
+                table: PhantomData,
+            }
+        }
+    }
+
+    /// A column of table `T` holding values of the rust type `V`.
+    ///
+    /// Typed columns are generated by `#[derive(Schema)]` and returned by the `col()` method of
+    /// the table, named after their fields (e.g. `User::col().created_at`). They let queries over
+    /// columns, such as aggregates, be checked against the field types at compile time.
+    pub struct TypedColumn<T: Table, V> {
+        column: Column<T>,
+        value: PhantomData<fn() -> V>,
+    }
+
+    impl<T: Table, V> TypedColumn<T, V> {
+        /// Declares `column` to hold values of type `V`. Only used by generated code.
+        #[doc(hidden)]
+        pub const fn new(column: Column<T>) -> Self {
+            Self {
+                column,
+                value: PhantomData,
+            }
+        }
+
+        /// The untyped column
+        pub const fn column(&self) -> &Column<T> {
+            &self.column
+        }
+    }
+
+    impl<T: Table, V> Clone for TypedColumn<T, V> {
+        fn clone(&self) -> Self {
+            Self {
+                column: self.column.clone(),
+                value: PhantomData,
+            }
+        }
+    }
+
+    impl<T: Table, V> std::fmt::Debug for TypedColumn<T, V> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_tuple("TypedColumn")
+                .field(&self.column.field())
+                .finish()
+        }
+    }
 }
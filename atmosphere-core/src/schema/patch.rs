@@ -0,0 +1,19 @@
+use crate::{Bind, Bindable, Column, Result};
+
+/// A partial update of a row.
+///
+/// Patches hold an optional value for every column of their table except for the primary key
+/// and are applied using [`Update::apply_patch`](crate::Update::apply_patch), which only sets the
+/// columns provided by the patch. They are generated by `#[derive(Schema)]` if the `#[patch]`
+/// attribute is used on the table (e.g. `UserPatch` for `User`), making them a good fit for
+/// partial API payloads.
+pub trait Patch: Send + Sync {
+    /// The table this patch is applied to.
+    type Table: Bind;
+
+    /// The columns for which this patch provides a value.
+    fn columns(&self) -> Vec<Column<Self::Table>>;
+
+    /// Binds the value this patch provides for a single column to a given query.
+    fn bind<'q, Q: Bindable<'q>>(&'q self, c: &'q Column<Self::Table>, query: Q) -> Result<Q>;
+}
@@ -0,0 +1,79 @@
+use crate::{
+    filter::Filter,
+    hooks::{self, HookInput, HookStage, Hooks},
+    query::QueryError,
+    Bind, Column, Error, Result,
+};
+
+use async_trait::async_trait;
+use sqlx::{database::HasArguments, Database, Executor, FromRow, IntoArguments};
+
+/// A subset of the columns of a table.
+///
+/// Projections only select their own columns, which avoids transferring large columns of wide
+/// tables that are not needed. They are implemented by `#[derive(Projection)]`, which checks
+/// every field of the projection against the typed column of the same name of the table at
+/// compile time.
+///
+/// Hooks of the table are executed before binding and before execution, without any row as
+/// input.
+#[async_trait]
+pub trait Projection:
+    for<'r> FromRow<'r, <crate::Driver as Database>::Row> + Send + Unpin + 'static
+{
+    /// The table this projection selects from
+    type Table: Bind + Hooks + Send + Sync + Unpin;
+
+    /// The projected columns, in the order they are decoded from rows.
+    fn columns() -> Vec<Column<Self::Table>>;
+
+    /// Finds the projection of the row with the primary key `pk`.
+    async fn find<'e, E>(
+        pk: &<Self::Table as crate::Table>::PrimaryKey,
+        executor: E,
+    ) -> Result<Self>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::project_by::<Self::Table>(
+            &Self::columns(),
+            Column::PrimaryKey(&<Self::Table as crate::Table>::PRIMARY_KEY),
+        );
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::PrimaryKey(pk)).await?;
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        sqlx::query_as(query.sql())
+            .bind(pk)
+            .fetch_one(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self::Table>(query.op))
+            .map_err(Error::Query)
+    }
+
+    /// Finds the projections of all rows matching `filter`.
+    async fn find_where<'e, E>(filter: &Filter<Self::Table>, executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::project::<Self::Table>(&Self::columns(), filter)?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        sqlx::query_as_with(query.sql(), args)
+            .persistent(false)
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self::Table>(query.op))
+            .map_err(Error::Query)
+    }
+}
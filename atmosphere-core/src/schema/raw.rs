@@ -0,0 +1,196 @@
+use std::marker::PhantomData;
+
+use crate::{
+    filter::Value,
+    hooks::{self, HookInput, HookStage, Hooks},
+    query::{Cardinality, Operation, Query, QueryError, QueryResult},
+    runtime::sql::{self, Bindings},
+    Bind, Error, Result, Table,
+};
+
+use sqlx::{database::HasArguments, Encode, Executor, IntoArguments, QueryBuilder, Type};
+
+/// Raw sql queries over a table, for queries the builders can not express.
+///
+/// Raw queries map their rows to entities, run the hooks of the table and map errors to
+/// [`QueryError`] like the queries generated by atmosphere. Identifiers of the table are
+/// interpolated into the sql (see [`sql::interpolate`]), while values are bound as parameters:
+///
+/// ```ignore
+/// let users = User::query_raw("SELECT * FROM {table} WHERE {email} LIKE $1 ORDER BY {name}")
+///     .bind("%@example.com")
+///     .fetch_all(&pool)
+///     .await?;
+/// ```
+pub trait Raw: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
+    /// Creates a raw query from `sql`, interpolating the identifiers of the table.
+    fn query_raw(sql: &str) -> RawQuery<Self> {
+        RawQuery {
+            sql: sql::interpolate::<Self>(sql),
+            values: vec![],
+            table: PhantomData,
+        }
+    }
+}
+
+impl<T> Raw for T where T: Table + Bind + Hooks + Send + Sync + Unpin + 'static {}
+
+/// A raw sql query over table `T`, created by [`Raw::query_raw`].
+///
+/// Hooks are executed before binding and before execution without any row as input, and after
+/// execution with the result of the query. Errors of the interpolation are returned once the
+/// query is executed.
+pub struct RawQuery<T: Table> {
+    sql: Result<String>,
+    values: Vec<Box<dyn Value>>,
+    table: PhantomData<T>,
+}
+
+impl<T: Table + Bind + Hooks + Send + Sync + Unpin + 'static> RawQuery<T> {
+    /// Binds `value` to the next parameter of the query.
+    pub fn bind<V>(mut self, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, crate::Driver> + Type<crate::Driver> + Send + Sync + 'static,
+    {
+        self.values.push(Box::new(value));
+        self
+    }
+
+    /// Executes the query and returns the only resulting row, failing if there is none.
+    pub async fn fetch_one<'e, E>(self, executor: E) -> Result<T>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let (query, args) = prepare::<T>(self.sql, &self.values, Cardinality::One).await?;
+
+        let res = sqlx::query_as_with(query.sql(), args)
+            .persistent(false)
+            .fetch_one(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(HookStage::PostExec, &query, QueryResult::One(&res).into()).await?;
+
+        res
+    }
+
+    /// Executes the query and returns the resulting row, if any.
+    pub async fn fetch_optional<'e, E>(self, executor: E) -> Result<Option<T>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let (query, args) = prepare::<T>(self.sql, &self.values, Cardinality::One).await?;
+
+        let res = sqlx::query_as_with(query.sql(), args)
+            .persistent(false)
+            .fetch_optional(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(
+            HookStage::PostExec,
+            &query,
+            QueryResult::Optional(&res).into(),
+        )
+        .await?;
+
+        res
+    }
+
+    /// Executes the query and returns all resulting rows.
+    pub async fn fetch_all<'e, E>(self, executor: E) -> Result<Vec<T>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let (query, args) = prepare::<T>(self.sql, &self.values, Cardinality::Many).await?;
+
+        let res = sqlx::query_as_with(query.sql(), args)
+            .persistent(false)
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(HookStage::PostExec, &query, QueryResult::Many(&res).into()).await?;
+
+        res
+    }
+
+    /// Executes the query without returning rows.
+    pub async fn execute<'e, E>(
+        self,
+        executor: E,
+    ) -> Result<<crate::Driver as sqlx::Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let (query, args) = prepare::<T>(self.sql, &self.values, Cardinality::Many).await?;
+
+        let res = sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .execute(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(
+            HookStage::PostExec,
+            &query,
+            QueryResult::Execution(&res).into(),
+        )
+        .await?;
+
+        res
+    }
+}
+
+/// Prepares a raw query for execution, executing the hooks up to `PreExec`.
+async fn prepare<'q, T: Hooks + Sync>(
+    sql: Result<String>,
+    values: &'q [Box<dyn Value>],
+    cardinality: Cardinality,
+) -> Result<(Query<T>, <crate::Driver as HasArguments<'q>>::Arguments)> {
+    let sql = sql?;
+
+    let query = Query::new(
+        operation(&sql),
+        cardinality,
+        QueryBuilder::new(sql),
+        Bindings::empty(),
+    );
+
+    hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+    let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+
+    for value in values {
+        value.add(&mut args);
+    }
+
+    hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+    Ok((query, args))
+}
+
+/// Infers the operation of raw sql from its leading keyword
+fn operation(sql: &str) -> Operation {
+    let keyword = sql.split_whitespace().next().unwrap_or_default();
+
+    match keyword.to_uppercase().as_str() {
+        "SELECT" => Operation::Select,
+        "INSERT" => Operation::Insert,
+        "UPDATE" => Operation::Update,
+        "DELETE" => Operation::Delete,
+        _ => Operation::Other,
+    }
+}
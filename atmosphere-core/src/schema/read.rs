@@ -1,11 +1,18 @@
+#[cfg(not(feature = "sqlite"))]
+use crate::runtime::sql::{self, Lock};
 use crate::{
+    filter::Filter,
     hooks::{self, HookInput, HookStage, Hooks},
     query::{QueryError, QueryResult},
     schema::Table,
     Bind, Error, Result,
 };
 
+use std::future::poll_fn;
+
+use async_stream::try_stream;
 use async_trait::async_trait;
+use futures_core::stream::BoxStream;
 use sqlx::{database::HasArguments, Executor, IntoArguments};
 
 /// Trait for reading rows from a database.
@@ -34,6 +41,23 @@ pub trait Read: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
 
+    /// Checks whether a row with the primary key `pk` exists, without retrieving it. Hooks are
+    /// executed before binding and before execution.
+    async fn exists<'e, E>(pk: &Self::PrimaryKey, executor: E) -> Result<bool>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Checks whether any row matches `filter` (`Filter::new()` matches all rows), without
+    /// retrieving it. Hooks are executed before binding and before execution, without any row as
+    /// input.
+    async fn exists_where<'e, E>(filter: &Filter<Self>, executor: E) -> Result<bool>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
     /// Retrieves all rows from the table. This method is useful for fetching the complete
     /// dataset of a table, executing a query to return all rows, and applying hooks as needed.
     async fn find_all<'e, E>(executor: E) -> Result<Vec<Self>>
@@ -42,6 +66,17 @@ pub trait Read: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
 
+    /// Like `find_all`, but streams the rows instead of collecting them, which allows processing
+    /// large tables with constant memory. The query is executed once the stream is polled.
+    ///
+    /// As the rows are not collected, the `PostExec` hooks are executed for every row (with
+    /// `QueryResult::One`) instead of once for all rows.
+    fn stream_all<'e, E>(executor: E) -> BoxStream<'e, Result<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver> + 'e,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
     /// Reloads the current entity from the database. This method is designed to update the entity
     /// instance with the latest data from the database, ensuring that it reflects the current
     /// state of the corresponding row.
@@ -51,6 +86,54 @@ pub trait Read: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
 
+    /// Finds a row by its primary key and locks it for the enclosing transaction (`FOR UPDATE`),
+    /// waiting for concurrent locks on the row to be released.
+    ///
+    /// Not available with the `sqlite` driver.
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_for_update<'e, E>(pk: &Self::PrimaryKey, executor: E) -> Result<Self>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Finds a row by its primary key and locks it for the enclosing transaction, unless it is
+    /// locked by a concurrent transaction (`FOR UPDATE SKIP LOCKED`), in which case `None` is
+    /// returned.
+    ///
+    /// Not available with the `sqlite` driver.
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_for_update_skip_locked<'e, E>(
+        pk: &Self::PrimaryKey,
+        executor: E,
+    ) -> Result<Option<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Retrieves all rows from the table and locks them for the enclosing transaction (`FOR
+    /// UPDATE`), waiting for concurrent locks to be released.
+    ///
+    /// Not available with the `sqlite` driver.
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_all_for_update<'e, E>(executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Retrieves and locks all rows from the table which are not locked by concurrent
+    /// transactions (`FOR UPDATE SKIP LOCKED`), e.g. to claim jobs from a queue.
+    ///
+    /// Not available with the `sqlite` driver.
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_all_for_update_skip_locked<'e, E>(executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
     // Find all rows in the list of primary keys
     //async fn find_many<'e, E>(pks: &[impl AsRef<Self::PrimaryKey>], executor: E) -> Result<Vec<Self>>
     //where
@@ -87,10 +170,9 @@ where
 
         let res = sqlx::query_as(query.sql())
             .bind(pk)
-            .persistent(false)
             .fetch_one(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -121,10 +203,9 @@ where
 
         let res = sqlx::query_as(query.sql())
             .bind(pk)
-            .persistent(false)
             .fetch_optional(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -137,6 +218,50 @@ where
         res
     }
 
+    async fn exists<'e, E>(pk: &Self::PrimaryKey, executor: E) -> Result<bool>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::exists_by::<T>(T::PRIMARY_KEY.as_col());
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::PrimaryKey(pk)).await?;
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        sqlx::query(query.sql())
+            .bind(pk)
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some())
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query)
+    }
+
+    async fn exists_where<'e, E>(filter: &Filter<Self>, executor: E) -> Result<bool>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = crate::runtime::sql::exists_where::<T>(filter)?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some())
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query)
+    }
+
     async fn reload<'e, E>(&mut self, executor: E) -> Result<()>
     where
         E: Executor<'e, Database = crate::Driver>,
@@ -156,10 +281,9 @@ where
         hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
 
         let res = sql
-            .persistent(false)
             .fetch_one(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -186,10 +310,9 @@ where
         hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
 
         let res = sqlx::query_as(query.sql())
-            .persistent(false)
             .fetch_all(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -201,4 +324,145 @@ where
 
         res
     }
+
+    fn stream_all<'e, E>(executor: E) -> BoxStream<'e, Result<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver> + 'e,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        Box::pin(try_stream! {
+            let query = crate::runtime::sql::select_all::<T>();
+
+            hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+            hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+            let mut rows = sqlx::query_as(query.sql())
+                .fetch(executor);
+
+            while let Some(row) = poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+                let res = row
+                    .map_err(|e| QueryError::from(e).within::<T>(query.op))
+                    .map_err(Error::Query);
+
+                hooks::execute(
+                    hooks::HookStage::PostExec,
+                    &query,
+                    QueryResult::One(&res).into(),
+                )
+                .await?;
+
+                yield res?;
+            }
+        })
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_for_update<'e, E>(pk: &Self::PrimaryKey, executor: E) -> Result<Self>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = sql::select_by_locked::<T>(T::PRIMARY_KEY.as_col(), Lock::ForUpdate);
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::PrimaryKey(pk)).await?;
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let res = sqlx::query_as(query.sql())
+            .bind(pk)
+            .fetch_one(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::One(&res).into(),
+        )
+        .await?;
+
+        res
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_for_update_skip_locked<'e, E>(
+        pk: &Self::PrimaryKey,
+        executor: E,
+    ) -> Result<Option<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let query = sql::select_by_locked::<T>(T::PRIMARY_KEY.as_col(), Lock::ForUpdateSkipLocked);
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::PrimaryKey(pk)).await?;
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let res = sqlx::query_as(query.sql())
+            .bind(pk)
+            .fetch_optional(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::Optional(&res).into(),
+        )
+        .await?;
+
+        res
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_all_for_update<'e, E>(executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        find_all_locked(Lock::ForUpdate, executor).await
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn find_all_for_update_skip_locked<'e, E>(executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        find_all_locked(Lock::ForUpdateSkipLocked, executor).await
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+async fn find_all_locked<'e, T, E>(lock: Lock, executor: E) -> Result<Vec<T>>
+where
+    T: Table + Bind + Hooks + Send + Sync + Unpin + 'static,
+    E: Executor<'e, Database = crate::Driver>,
+    for<'q> <crate::Driver as HasArguments<'q>>::Arguments: IntoArguments<'q, crate::Driver> + Send,
+{
+    let query = sql::select_all_locked::<T>(lock);
+
+    hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+    hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+    let res = sqlx::query_as(query.sql())
+        .fetch_all(executor)
+        .await
+        .map_err(|e| QueryError::from(e).within::<T>(query.op))
+        .map_err(Error::Query);
+
+    hooks::execute(
+        hooks::HookStage::PostExec,
+        &query,
+        QueryResult::Many(&res).into(),
+    )
+    .await?;
+
+    res
 }
@@ -0,0 +1,68 @@
+use crate::{
+    hooks::{self, HookInput, HookStage, Hooks},
+    query::{QueryError, QueryResult},
+    Bind, DataColumn, Error, Result, Table,
+};
+
+use async_trait::async_trait;
+use sqlx::{database::HasArguments, Executor, IntoArguments};
+
+/// Full-text search over the searchable columns of a table.
+///
+/// Implemented by `#[derive(Schema)]` for tables with data columns marked as
+/// `#[sql(searchable)]`, which must be text columns. The search uses the full-text search of the
+/// database and returns the matching rows ordered by their relevance:
+///
+/// - `postgres`: matches `to_tsvector(config, ..)` of the concatenated columns against
+///   `websearch_to_tsquery(config, $1)`, ranked by `ts_rank`, using the text search configuration
+///   [`Search::CONFIG`]. An expression index using the same expression speeds up searches, e.g.
+///   `CREATE INDEX ON post USING GIN (to_tsvector('english', coalesce(body, '') || ' ' ||
+///   coalesce(title, '')))` for the searchable columns `body` and `title` in the order of their
+///   fields.
+/// - `sqlite`: matches the FTS5 table `<table>_fts` indexing the searchable columns, ranked by
+///   `rank`. The FTS5 table must use the rowids of the table, e.g. as an external content table
+///   (`CREATE VIRTUAL TABLE post_fts USING fts5(title, body, content='post')`) kept in sync by
+///   triggers.
+/// - `mysql`: matches `MATCH (..) AGAINST ($1)` in natural language mode, ranked by the match
+///   score. Requires a `FULLTEXT` index on the searchable columns.
+///
+/// ```ignore
+/// let posts = Post::search("rust -java", &pool).await?;
+/// ```
+///
+/// Hooks are executed like for [`Read::find_all`](crate::Read::find_all).
+#[async_trait]
+pub trait Search: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
+    /// The searchable data columns
+    const SEARCH_COLUMNS: &'static [DataColumn<Self>];
+
+    /// The text search configuration of `postgres`, set by `#[table(search_config = "..")]`.
+    /// Passed explicitly instead of using `default_text_search_config`, so that the results do
+    /// not depend on the session and the search expression can be indexed.
+    const CONFIG: &'static str = "english";
+
+    /// Searches the searchable columns for `query` and returns the matching rows, most relevant
+    /// first.
+    async fn search<'e, E>(query: &str, executor: E) -> Result<Vec<Self>>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let search = crate::runtime::sql::search::<Self>(Self::SEARCH_COLUMNS, Self::CONFIG);
+
+        hooks::execute(HookStage::PreBind, &search, HookInput::None).await?;
+        hooks::execute(HookStage::PreExec, &search, HookInput::None).await?;
+
+        let res = sqlx::query_as(search.sql())
+            .bind(query)
+            .fetch_all(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<Self>(search.op))
+            .map_err(Error::Query);
+
+        hooks::execute(HookStage::PostExec, &search, QueryResult::Many(&res).into()).await?;
+
+        res
+    }
+}
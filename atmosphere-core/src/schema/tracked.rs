@@ -0,0 +1,109 @@
+use std::ops::{Deref, DerefMut};
+
+use sqlx::{database::HasArguments, Database, Executor, IntoArguments};
+
+use crate::{
+    schema::{Column, Table, Update},
+    Bind, Result,
+};
+
+/// An entity with change tracking.
+///
+/// `Tracked` keeps a snapshot of the entity as it was loaded (or last written) and dereferences
+/// to the entity itself, so that it can be modified as usual. [`Tracked::update`] compares the
+/// entity to its snapshot and only sets the modified columns, which avoids overwriting concurrent
+/// changes to unrelated columns.
+///
+/// ```ignore
+/// let mut user = Tracked::new(User::find(&1, &pool).await?);
+///
+/// user.name = "Jane".to_owned();
+///
+/// // UPDATE "public"."user" SET name = $2 WHERE id = $1
+/// user.update(&pool).await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tracked<T: Table> {
+    entity: T,
+    snapshot: T,
+}
+
+impl<T: Bind + Clone> Tracked<T> {
+    /// Starts tracking the changes to `entity`.
+    pub fn new(entity: T) -> Self {
+        Self {
+            snapshot: entity.clone(),
+            entity,
+        }
+    }
+
+    /// Stops tracking and returns the entity.
+    pub fn into_inner(self) -> T {
+        self.entity
+    }
+
+    /// The columns whose values differ from the snapshot.
+    pub fn changes(&self) -> Vec<Column<T>> {
+        T::FOREIGN_KEYS
+            .iter()
+            .map(Column::ForeignKey)
+            .chain(T::DATA_COLUMNS.iter().map(Column::Data))
+            .chain(T::TIMESTAMP_COLUMNS.iter().map(Column::Timestamp))
+            .filter(|c| self.entity.changed(c, &self.snapshot))
+            .collect()
+    }
+
+    /// Whether any column differs from the snapshot.
+    pub fn is_changed(&self) -> bool {
+        !self.changes().is_empty()
+    }
+}
+
+impl<T: Update + Clone> Tracked<T> {
+    /// Writes the modified columns of the entity and takes a new snapshot. If no column was
+    /// modified, no query is executed.
+    ///
+    /// Like [`Update::update`], this fails with `Error::StaleEntity` on versioned tables if the
+    /// row was modified concurrently.
+    pub async fn update<'e, E>(
+        &mut self,
+        executor: E,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let changes = self.changes();
+
+        if changes.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let res = self.entity.update_columns(&changes, executor).await?;
+
+        self.snapshot = self.entity.clone();
+
+        Ok(res)
+    }
+}
+
+impl<T: Bind + Clone> From<T> for Tracked<T> {
+    fn from(entity: T) -> Self {
+        Self::new(entity)
+    }
+}
+
+impl<T: Table> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entity
+    }
+}
+
+impl<T: Table> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entity
+    }
+}
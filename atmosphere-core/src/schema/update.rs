@@ -1,12 +1,14 @@
 use crate::{
+    bind::BindError,
+    filter::{Assignments, Filter},
     hooks::{self, HookInput, HookStage, Hooks},
     query::{QueryError, QueryResult},
-    schema::Table,
+    schema::{Column, Patch, Table},
     Bind, Error, Result,
 };
 
 use async_trait::async_trait;
-use sqlx::{database::HasArguments, Database, Executor, IntoArguments};
+use sqlx::{database::HasArguments, Acquire, Database, Executor, IntoArguments};
 
 /// Update rows in a database.
 ///
@@ -18,6 +20,10 @@ pub trait Update: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
     /// Updates an existing row in the database. This method constructs an update query, binds the
     /// necessary values, executes the query, and applies hooks at predefined stages (e.g., before
     /// binding, before execution, after execution).
+    ///
+    /// If the table has a version column, the row is only updated if its version matches the
+    /// version of `self`; otherwise `Error::StaleEntity` is returned. On success, the version of
+    /// `self` is incremented.
     async fn update<'e, E>(
         &mut self,
         executor: E,
@@ -27,9 +33,42 @@ pub trait Update: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
 
+    /// Like `update`, but only sets the given columns, leaving all other columns of the row
+    /// untouched. This is used by [`Tracked`](crate::Tracked) to write only modified columns. If no
+    /// column is given, no query is executed.
+    async fn update_columns<'e, E>(
+        &mut self,
+        columns: &[Column<Self>],
+        executor: E,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Applies a patch to the row with the primary key `pk`, only setting the columns provided by
+    /// the patch. If no column is provided, no query is executed.
+    ///
+    /// If the table has a version column, it is incremented without being checked, which lets
+    /// concurrent holders of the row detect the change.
+    async fn apply_patch<'e, E, P>(
+        pk: &Self::PrimaryKey,
+        patch: &P,
+        executor: E,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        P: Patch<Table = Self>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
     /// Similar to `update`, but uses an upsert approach. It either updates an existing row or
     /// inserts a new one if it does not exist, depending on the primary key's presence and
     /// uniqueness.
+    ///
+    /// If the table has a version column, an existing row is only updated if its version matches
+    /// the version of `self`; otherwise `Error::StaleEntity` is returned. On success, the version
+    /// of `self` is incremented.
     async fn save<'e, E>(
         &mut self,
         executor: E,
@@ -38,6 +77,42 @@ pub trait Update: Table + Bind + Hooks + Send + Sync + Unpin + 'static {
         E: Executor<'e, Database = crate::Driver>,
         for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
             IntoArguments<'q, crate::Driver> + Send;
+
+    /// Saves many rows using multi-row upserts, resolving conflicts as described by `upsert`.
+    /// Like `Create::create_many`, the rows are split into as few statements as the bind
+    /// parameter limit of the driver allows, which are executed within a transaction (or
+    /// savepoint) begun on `conn`.
+    ///
+    /// If the table has a version column and conflicting rows are updated, every row must either
+    /// be inserted or match the version of its entity; otherwise `Error::StaleEntity` is returned
+    /// and no row is saved. On success, the versions of the entities are incremented.
+    async fn save_many<'a, A>(
+        entities: &mut [Self],
+        upsert: &Upsert<Self>,
+        conn: A,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        A: Acquire<'a, Database = crate::Driver> + Send,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
+
+    /// Assigns values to the columns of all rows matching `filter` in a single statement and
+    /// returns the number of affected rows. Hooks are executed once for the statement, without
+    /// any row as input. If no column is assigned, the filter is validated but no query is
+    /// executed.
+    ///
+    /// Filters without conditions are refused with `Error::EmptyFilter` unless they were created
+    /// using `Filter::all`. If the table has a version column, it is incremented without being
+    /// checked, which lets concurrent holders of the rows detect the change.
+    async fn update_where<'e, E>(
+        filter: &Filter<Self>,
+        assignments: &Assignments<Self>,
+        executor: E,
+    ) -> Result<u64>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send;
 }
 
 #[async_trait]
@@ -66,11 +141,112 @@ where
 
         hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
 
+        let res = sql
+            .execute(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query)
+            .and_then(versioned::<T>);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::Execution(&res).into(),
+        )
+        .await?;
+
+        if res.is_ok() {
+            self.increment_version();
+        }
+
+        res
+    }
+
+    async fn update_columns<'e, E>(
+        &mut self,
+        columns: &[Column<Self>],
+        executor: E,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        if columns.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let query = crate::runtime::sql::update_columns::<T>(columns.iter().cloned());
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::Row(self)).await?;
+
+        let mut sql = sqlx::query(query.sql());
+
+        for c in query.bindings().columns() {
+            sql = self.bind(c, sql).unwrap();
+        }
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
         let res = sql
             .persistent(false)
             .execute(executor)
             .await
-            .map_err(QueryError::from)
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query)
+            .and_then(versioned::<T>);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::Execution(&res).into(),
+        )
+        .await?;
+
+        if res.is_ok() {
+            self.increment_version();
+        }
+
+        res
+    }
+
+    async fn apply_patch<'e, E, P>(
+        pk: &Self::PrimaryKey,
+        patch: &P,
+        executor: E,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        P: Patch<Table = Self>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let columns = patch.columns();
+
+        if columns.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let query = crate::runtime::sql::patch::<T>(columns);
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::PrimaryKey(pk)).await?;
+
+        let mut sql = sqlx::query(query.sql());
+
+        for c in query.bindings().columns() {
+            sql = match c {
+                Column::PrimaryKey(_) => sql.bind(pk),
+                c => patch.bind(c, sql).unwrap(),
+            };
+        }
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let res = sql
+            .persistent(false)
+            .execute(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -102,10 +278,139 @@ where
         hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
 
         let res = sql
-            .persistent(false)
             .execute(executor)
             .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
+            .map_err(Error::Query)
+            .and_then(versioned::<T>);
+
+        hooks::execute(
+            hooks::HookStage::PostExec,
+            &query,
+            QueryResult::Execution(&res).into(),
+        )
+        .await?;
+
+        if res.is_ok() {
+            self.increment_version();
+        }
+
+        res
+    }
+
+    async fn save_many<'a, A>(
+        entities: &mut [Self],
+        upsert: &Upsert<Self>,
+        conn: A,
+    ) -> Result<<crate::Driver as Database>::QueryResult>
+    where
+        A: Acquire<'a, Database = crate::Driver> + Send,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        let mut total = <crate::Driver as Database>::QueryResult::default();
+
+        if entities.is_empty() {
+            return Ok(total);
+        }
+
+        let updates = upsert.columns()?.is_some();
+
+        let mut tx = conn
+            .begin()
+            .await
             .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        for chunk in entities.chunks_mut(crate::runtime::sql::insert_many_chunk::<T>()) {
+            let query = crate::runtime::sql::upsert_with::<T>(chunk.len(), upsert)?;
+
+            for entity in chunk.iter_mut() {
+                hooks::execute(HookStage::PreBind, &query, HookInput::Row(entity)).await?;
+            }
+
+            let mut sql = sqlx::query(query.sql());
+
+            for entity in chunk.iter() {
+                for c in query.bindings().columns() {
+                    sql = entity.bind(c, sql).unwrap();
+                }
+            }
+
+            hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+            let rows = chunk.len() as u64;
+
+            let res = sql
+                .persistent(false)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| QueryError::from(e).within::<T>(query.op))
+                .map_err(Error::Query)
+                .and_then(|res| {
+                    if updates && T::VERSION_COLUMN.is_some() && res.rows_affected() < rows {
+                        return Err(Error::StaleEntity);
+                    }
+
+                    Ok(res)
+                });
+
+            hooks::execute(
+                hooks::HookStage::PostExec,
+                &query,
+                QueryResult::Execution(&res).into(),
+            )
+            .await?;
+
+            total.extend(Some(res?));
+        }
+
+        tx.commit()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        if updates {
+            for entity in entities.iter_mut() {
+                entity.increment_version();
+            }
+        }
+
+        Ok(total)
+    }
+
+    async fn update_where<'e, E>(
+        filter: &Filter<Self>,
+        assignments: &Assignments<Self>,
+        executor: E,
+    ) -> Result<u64>
+    where
+        E: Executor<'e, Database = crate::Driver>,
+        for<'q> <crate::Driver as HasArguments<'q>>::Arguments:
+            IntoArguments<'q, crate::Driver> + Send,
+    {
+        if assignments.is_empty() {
+            // the filter is still validated, so that invalid filters are reported regardless
+            filter.sql(&mut 0)?;
+            return Ok(0);
+        }
+
+        let query = crate::runtime::sql::update_where::<T>(filter, assignments)?;
+
+        hooks::execute(HookStage::PreBind, &query, HookInput::None).await?;
+
+        let mut args = <crate::Driver as HasArguments<'_>>::Arguments::default();
+
+        assignments.arguments(&mut args);
+        filter.arguments(&mut args);
+
+        hooks::execute(HookStage::PreExec, &query, HookInput::None).await?;
+
+        let res = sqlx::query_with(query.sql(), args)
+            .persistent(false)
+            .execute(executor)
+            .await
+            .map_err(|e| QueryError::from(e).within::<T>(query.op))
             .map_err(Error::Query);
 
         hooks::execute(
@@ -115,6 +420,143 @@ where
         )
         .await?;
 
-        res
+        res.map(|res| res.rows_affected())
+    }
+}
+
+/// Describes how upserts resolve conflicts with existing rows: the conflict target and which
+/// columns of conflicting rows are updated.
+///
+/// By default, conflicts on the primary key update every column. Conflict targets on unique
+/// columns are created through the `on_conflict_<column>` functions generated for every
+/// `#[sql(unique)]` column, ensuring that the target is backed by a unique constraint.
+///
+/// ```ignore
+/// // update the email of users with conflicting usernames, leave all other columns untouched
+/// User::save_many(&mut users, &User::on_conflict_username().update(["email"]), &pool).await?;
+///
+/// // skip conflicting users
+/// User::save_many(&mut users, &Upsert::default().do_nothing(), &pool).await?;
+/// ```
+pub struct Upsert<T: Table> {
+    target: Column<T>,
+    action: Action,
+}
+
+enum Action {
+    UpdateAll,
+    Update(Vec<&'static str>),
+    Keep(Vec<&'static str>),
+    Nothing,
+}
+
+impl<T: Table> Default for Upsert<T> {
+    fn default() -> Self {
+        Self {
+            target: Column::PrimaryKey(&T::PRIMARY_KEY),
+            action: Action::UpdateAll,
+        }
+    }
+}
+
+impl<T: Table> Upsert<T> {
+    /// Uses `target` as the conflict target. The column must be backed by a unique constraint,
+    /// which is why this is only used by the generated `on_conflict_<column>` functions.
+    #[doc(hidden)]
+    pub const fn on(target: Column<T>) -> Self {
+        Self {
+            target,
+            action: Action::UpdateAll,
+        }
+    }
+
+    /// Only updates the columns of the given fields on conflict.
+    pub fn update(self, fields: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            action: Action::Update(fields.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Updates all columns except for the columns of the given fields on conflict.
+    pub fn keep(self, fields: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            action: Action::Keep(fields.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Leaves conflicting rows untouched.
+    pub fn do_nothing(self) -> Self {
+        Self {
+            action: Action::Nothing,
+            ..self
+        }
     }
+
+    /// The conflict target
+    pub const fn target(&self) -> &Column<T> {
+        &self.target
+    }
+
+    /// The columns to update on conflict, or `None` if conflicting rows are left untouched. The
+    /// version column is always updated along with other columns.
+    pub fn columns(&self) -> Result<Option<Vec<Column<T>>>> {
+        let fields = match &self.action {
+            Action::Update(fields) | Action::Keep(fields) => fields.as_slice(),
+            Action::UpdateAll => &[],
+            Action::Nothing => return Ok(None),
+        };
+
+        let all: Vec<Column<T>> = T::FOREIGN_KEYS
+            .iter()
+            .map(Column::ForeignKey)
+            .chain(T::DATA_COLUMNS.iter().map(Column::Data))
+            .chain(T::TIMESTAMP_COLUMNS.iter().map(Column::Timestamp))
+            .collect();
+
+        if let Some(unknown) = fields
+            .iter()
+            .find(|field| !all.iter().any(|c| c.field() == **field))
+        {
+            return Err(Error::Bind(BindError::Unknown(unknown)));
+        }
+
+        let columns: Vec<Column<T>> = all
+            .into_iter()
+            .filter(|c| match &self.action {
+                Action::Update(fields) => fields.contains(&c.field()),
+                Action::Keep(fields) => !fields.contains(&c.field()),
+                _ => true,
+            })
+            .collect();
+
+        if columns.is_empty() {
+            return Ok(None);
+        }
+
+        let version = T::VERSION_COLUMN.as_ref().map(|v| Column::Version(v));
+
+        // keep the column order of the insert
+        let (timestamps, mut columns): (Vec<_>, Vec<_>) = columns
+            .into_iter()
+            .partition(|c| matches!(c, Column::Timestamp(_)));
+
+        columns.extend(version);
+        columns.extend(timestamps);
+
+        Ok(Some(columns))
+    }
+}
+
+/// Detects lost updates on versioned tables: if no row was affected, the row was either deleted
+/// or its version was incremented by a concurrent update.
+fn versioned<T: Table>(
+    res: <crate::Driver as Database>::QueryResult,
+) -> Result<<crate::Driver as Database>::QueryResult> {
+    if T::VERSION_COLUMN.is_some() && res.rows_affected() == 0 {
+        return Err(Error::StaleEntity);
+    }
+
+    Ok(res)
 }
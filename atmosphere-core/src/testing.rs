@@ -28,8 +28,18 @@ where
         "instance was found (find_optional) before it was created"
     );
 
+    assert!(
+        !E::exists(instance.pk(), pool).await.unwrap(),
+        "instance exists before it was created"
+    );
+
     instance.create(pool).await.expect("insertion did not work");
 
+    assert!(
+        E::exists(instance.pk(), pool).await.unwrap(),
+        "instance does not exist after insertion"
+    );
+
     let retrieved = E::find(instance.pk(), pool)
         .await
         .expect("instance not found after insertion");
@@ -136,6 +146,11 @@ where
         "instance was found (find_optional) after deletion"
     );
 
+    assert!(
+        !E::exists(instance.pk(), pool).await.unwrap(),
+        "instance exists after deletion"
+    );
+
     instance.create(pool).await.expect("insertion did not work");
 
     E::delete_by(instance.pk(), pool)
@@ -0,0 +1,437 @@
+//! Transaction Handling
+//!
+//! This module provides a transaction type which can be used as an executor for all atmosphere
+//! traits (`Create`, `Read`, `Update`, `Delete`) and relationship methods. It supports nested
+//! savepoints, callbacks on commit and rollback and is guaranteed to roll back if it is dropped
+//! without being committed.
+//!
+//! Under high contention, databases abort transactions to resolve conflicts (serialization
+//! failures, deadlocks, lock timeouts). Such transactions are safe to re-run as a whole, which
+//! `transaction_with_retry` does with an exponential backoff between the attempts.
+//!
+//! ```ignore
+//! use atmosphere::transaction::{transaction_with_retry, RetryPolicy, Transaction};
+//!
+//! let mut tx = Transaction::begin(&pool).await?;
+//!
+//! forest.create(&mut tx).await?;
+//!
+//! tx.savepoint(|sp| {
+//!     Box::pin(async move {
+//!         tree.create(&mut *sp).await?;
+//!         Ok(())
+//!     })
+//! })
+//! .await?;
+//!
+//! tx.on_commit(|| println!("forest planted"));
+//! tx.commit().await?;
+//!
+//! transaction_with_retry(&pool, RetryPolicy::default(), |tx| {
+//!     Box::pin(async move {
+//!         let mut forest = Forest::find(&1, &mut *tx).await?;
+//!         forest.name = "grunewald".to_owned();
+//!         forest.update(&mut *tx).await?;
+//!         Ok(())
+//!     })
+//! })
+//! .await?;
+//! ```
+
+use std::{fmt, mem, time::Duration};
+
+use futures_core::{future::BoxFuture, stream::BoxStream};
+use sqlx::{database::HasStatement, Acquire, Database, Describe, Either, Execute, Executor};
+
+use crate::{query::QueryError, Error, Result};
+
+type Callback = Box<dyn FnOnce() + Send + 'static>;
+
+/// Callbacks registered on a transaction. Pending rollback callbacks are run on drop, so that
+/// they are guaranteed to run if the transaction is not committed.
+#[derive(Default)]
+struct Callbacks {
+    on_commit: Vec<Callback>,
+    on_rollback: Vec<Callback>,
+}
+
+impl Callbacks {
+    fn commit(mut self) {
+        self.on_rollback.clear();
+
+        for f in mem::take(&mut self.on_commit) {
+            f();
+        }
+    }
+
+    fn rollback(mut self) {
+        self.on_commit.clear();
+
+        for f in mem::take(&mut self.on_rollback) {
+            f();
+        }
+    }
+}
+
+impl Drop for Callbacks {
+    fn drop(&mut self) {
+        for f in mem::take(&mut self.on_rollback) {
+            f();
+        }
+    }
+}
+
+/// A database transaction (or savepoint) on the atmosphere driver.
+///
+/// `&mut Transaction` implements `sqlx::Executor` and `sqlx::Acquire` and can therefore be passed
+/// to any atmosphere query. If a transaction is dropped without calling [`Transaction::commit`],
+/// it is rolled back and its rollback callbacks are run.
+pub struct Transaction<'c> {
+    inner: sqlx::Transaction<'c, crate::Driver>,
+    callbacks: Callbacks,
+    depth: usize,
+}
+
+impl Transaction<'static> {
+    /// Begins a new transaction on a connection acquired from `pool`.
+    pub async fn begin(pool: &crate::Pool) -> Result<Self> {
+        let inner = pool
+            .begin()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        Ok(Self {
+            inner,
+            callbacks: Callbacks::default(),
+            depth: 0,
+        })
+    }
+}
+
+impl<'c> Transaction<'c> {
+    /// The nesting depth of this transaction; `0` for a transaction, `n` for the `n`-th nested
+    /// savepoint.
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Registers a callback which is run once the outermost transaction committed.
+    pub fn on_commit(&mut self, f: impl FnOnce() + Send + 'static) {
+        self.callbacks.on_commit.push(Box::new(f));
+    }
+
+    /// Registers a callback which is run if the changes of this transaction are rolled back –
+    /// either explicitly, through a rolled back (outer) savepoint or transaction, or on drop.
+    pub fn on_rollback(&mut self, f: impl FnOnce() + Send + 'static) {
+        self.callbacks.on_rollback.push(Box::new(f));
+    }
+
+    /// Commits this transaction and runs its commit callbacks.
+    pub async fn commit(self) -> Result<()> {
+        let Self {
+            inner, callbacks, ..
+        } = self;
+
+        inner
+            .commit()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        callbacks.commit();
+
+        Ok(())
+    }
+
+    /// Rolls back this transaction and runs its rollback callbacks.
+    pub async fn rollback(self) -> Result<()> {
+        let Self {
+            inner, callbacks, ..
+        } = self;
+
+        let res = inner
+            .rollback()
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query);
+
+        callbacks.rollback();
+
+        res
+    }
+
+    /// Runs `f` within a savepoint of this transaction.
+    ///
+    /// If `f` succeeds, the savepoint is released and its callbacks are handed over to this
+    /// transaction. Otherwise, the changes made by `f` are rolled back to the savepoint and the
+    /// error of `f` is returned, leaving this transaction usable.
+    pub async fn savepoint<F, R>(&mut self, f: F) -> Result<R>
+    where
+        F: for<'s> FnOnce(&'s mut Transaction<'_>) -> BoxFuture<'s, Result<R>>,
+    {
+        let inner = Acquire::begin(&mut self.inner)
+            .await
+            .map_err(QueryError::from)
+            .map_err(Error::Query)?;
+
+        let mut savepoint = Transaction {
+            inner,
+            callbacks: Callbacks::default(),
+            depth: self.depth + 1,
+        };
+
+        match f(&mut savepoint).await {
+            Ok(res) => {
+                let Transaction {
+                    inner,
+                    mut callbacks,
+                    ..
+                } = savepoint;
+
+                inner
+                    .commit()
+                    .await
+                    .map_err(QueryError::from)
+                    .map_err(Error::Query)?;
+
+                let parent = &mut self.callbacks;
+                parent.on_commit.append(&mut callbacks.on_commit);
+                parent.on_rollback.append(&mut callbacks.on_rollback);
+
+                Ok(res)
+            }
+            Err(err) => {
+                // the original error is more meaningful than a failed rollback, which is retried
+                // once the connection is used again
+                let _ = savepoint.rollback().await;
+
+                Err(err)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transaction")
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, 't> Executor<'c> for &'c mut Transaction<'t> {
+    type Database = crate::Driver;
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<
+        'e,
+        std::result::Result<
+            Either<<crate::Driver as Database>::QueryResult, <crate::Driver as Database>::Row>,
+            sqlx::Error,
+        >,
+    >
+    where
+        'c: 'e,
+        E: Execute<'q, Self::Database> + 'q,
+    {
+        (&mut *self.inner).fetch_many(query)
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, std::result::Result<Option<<crate::Driver as Database>::Row>, sqlx::Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Self::Database> + 'q,
+    {
+        (&mut *self.inner).fetch_optional(query)
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [<crate::Driver as Database>::TypeInfo],
+    ) -> BoxFuture<
+        'e,
+        std::result::Result<<crate::Driver as HasStatement<'q>>::Statement, sqlx::Error>,
+    >
+    where
+        'c: 'e,
+    {
+        (&mut *self.inner).prepare_with(sql, parameters)
+    }
+
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, std::result::Result<Describe<crate::Driver>, sqlx::Error>>
+    where
+        'c: 'e,
+    {
+        (&mut *self.inner).describe(sql)
+    }
+}
+
+impl<'c, 't> Acquire<'c> for &'c mut Transaction<'t> {
+    type Database = crate::Driver;
+
+    type Connection = &'c mut <crate::Driver as Database>::Connection;
+
+    fn acquire(self) -> BoxFuture<'c, std::result::Result<Self::Connection, sqlx::Error>> {
+        Box::pin(async move { Ok(&mut *self.inner) })
+    }
+
+    fn begin(
+        self,
+    ) -> BoxFuture<'c, std::result::Result<sqlx::Transaction<'c, crate::Driver>, sqlx::Error>> {
+        Acquire::begin(&mut *self.inner)
+    }
+}
+
+/// Describes how often and how fast a failed transaction is re-run.
+///
+/// The backoff before the `n`-th retry is `initial_backoff * multiplier^(n - 1)`, capped at
+/// `max_backoff`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// The backoff before the first retry
+    pub initial_backoff: Duration,
+    /// The upper bound for the backoff between two attempts
+    pub max_backoff: Duration,
+    /// The factor by which the backoff grows with each retry
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy with the default backoff, attempting the transaction at most
+    /// `max_attempts` times.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the initial and the maximum backoff between two attempts.
+    pub const fn with_backoff(self, initial: Duration, max: Duration) -> Self {
+        Self {
+            initial_backoff: initial,
+            max_backoff: max,
+            ..self
+        }
+    }
+
+    /// Sets the factor by which the backoff grows with each retry.
+    pub const fn with_multiplier(self, multiplier: u32) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    /// The backoff after the given (1-based) attempt failed.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Runs `f` within a transaction on `pool`, committing it if `f` succeeds.
+///
+/// If `f` or the commit fail with a retryable error (see [`Error::is_retryable`]), the
+/// transaction is rolled back and `f` is re-run in a new transaction after a backoff, until
+/// `policy.max_attempts` is reached. All other errors are returned immediately.
+pub async fn transaction_with_retry<F, R>(
+    pool: &crate::Pool,
+    policy: RetryPolicy,
+    mut f: F,
+) -> Result<R>
+where
+    F: for<'t> FnMut(&'t mut Transaction<'static>) -> BoxFuture<'t, Result<R>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match transaction(pool, &mut f).await {
+            Err(err) if err.is_retryable() && attempt < policy.max_attempts => {
+                sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Sleeps using the async runtime selected by the `runtime-*` features.
+async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+
+    #[cfg(all(feature = "async-std", not(feature = "tokio")))]
+    async_std::task::sleep(duration).await;
+
+    // sqlx can not connect without a runtime either
+    #[cfg(not(any(feature = "tokio", feature = "async-std")))]
+    let _ = duration;
+}
+
+async fn transaction<F, R>(pool: &crate::Pool, f: &mut F) -> Result<R>
+where
+    F: for<'t> FnMut(&'t mut Transaction<'static>) -> BoxFuture<'t, Result<R>>,
+{
+    let mut tx = Transaction::begin(pool).await?;
+
+    match f(&mut tx).await {
+        Ok(res) => {
+            tx.commit().await?;
+            Ok(res)
+        }
+        Err(err) => {
+            // the original error is more meaningful than a failed rollback, which the database
+            // performs anyway once the connection is closed
+            let _ = tx.rollback().await;
+
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(100))
+            .with_multiplier(3);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(30));
+        assert_eq!(policy.backoff(3), Duration::from_millis(90));
+        assert_eq!(policy.backoff(4), Duration::from_millis(100));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(100));
+    }
+}
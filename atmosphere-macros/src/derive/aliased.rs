@@ -0,0 +1,47 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::schema::{column::Column, table::Table};
+
+pub fn aliased(table: &Table) -> TokenStream {
+    let ident = &table.ident;
+
+    let columns = std::iter::once(Column::PrimaryKey(table.primary_key.clone()))
+        .chain(table.foreign_keys.iter().cloned().map(Column::ForeignKey))
+        .chain(table.data_columns.iter().cloned().map(Column::Data))
+        .chain(
+            table
+                .timestamp_columns
+                .iter()
+                .cloned()
+                .map(Column::Timestamp),
+        )
+        .chain(table.version_column.iter().cloned().map(Column::Version));
+
+    let mut fields = TokenStream::new();
+
+    for column in columns {
+        let field = column.name().field();
+        let sql = column.name().sql();
+
+        fields.extend(quote!(
+            #field: row.try_get(format!("{}.{}", alias, stringify!(#sql)).as_str())?,
+        ));
+    }
+
+    quote!(
+        #[automatically_derived]
+        impl ::atmosphere::rel::FromAliasedRow for #ident {
+            fn from_aliased_row(
+                row: &<::atmosphere::Driver as ::atmosphere::sqlx::Database>::Row,
+                alias: &str,
+            ) -> ::std::result::Result<Self, ::atmosphere::sqlx::Error> {
+                use ::atmosphere::sqlx::Row;
+
+                Ok(Self {
+                    #fields
+                })
+            }
+        }
+    )
+}
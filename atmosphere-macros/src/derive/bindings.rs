@@ -14,7 +14,7 @@ pub fn bindings(table: &Table) -> TokenStream {
         let field = &table.primary_key.name.field();
 
         binds.extend(quote!(
-            if #col.field() == Self::PRIMARY_KEY.field {
+            if #col.field() == <Self as ::atmosphere::Table>::PRIMARY_KEY.field {
                 use ::atmosphere::Bindable;
                 return Ok(#query.dyn_bind(&self.#field));
             }
@@ -54,6 +54,38 @@ pub fn bindings(table: &Table) -> TokenStream {
         ));
     }
 
+    if let Some(version) = &table.version_column {
+        let field = version.name.field();
+
+        binds.extend(quote!(
+            if #col.field() == stringify!(#field) {
+                use ::atmosphere::Bindable;
+                return Ok(#query.dyn_bind(&self.#field));
+            }
+        ));
+    }
+
+    let snapshot = Ident::new("snapshot", proc_macro2::Span::call_site());
+
+    let mut changes = TokenStream::new();
+
+    let fields = table
+        .foreign_keys
+        .iter()
+        .map(|fk| fk.name.field())
+        .chain(table.data_columns.iter().map(|data| data.name.field()))
+        .chain(table.timestamp_columns.iter().map(|ts| ts.name.field()))
+        .chain(table.version_column.iter().map(|v| v.name.field()));
+
+    for field in fields {
+        changes.extend(quote!(
+            if #col.field() == stringify!(#field) {
+                use ::atmosphere::bind::change::{Compare as _, Fallback as _};
+                return (&::atmosphere::bind::change::Probe(&self.#field, &#snapshot.#field)).changed();
+            }
+        ));
+    }
+
     let ident = &table.ident;
 
     quote!(
@@ -73,6 +105,17 @@ pub fn bindings(table: &Table) -> TokenStream {
                     ::atmosphere::bind::BindError::Unknown(#col.field())
                 ))
             }
+
+            #[allow(clippy::needless_borrow)]
+            fn changed(
+                &self,
+                #col: &::atmosphere::Column<Self>,
+                #snapshot: &Self
+            ) -> bool {
+                #changes
+
+                true
+            }
         }
     )
 }
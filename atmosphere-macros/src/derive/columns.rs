@@ -0,0 +1,72 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::schema::{column::Column, table::Table};
+
+pub fn columns(table: &Table) -> TokenStream {
+    let Table { vis, ident, .. } = table;
+
+    let columns_ident = Ident::new(&format!("{ident}Columns"), ident.span());
+
+    let mut columns: Vec<Column> = std::iter::once(Column::PrimaryKey(table.primary_key.clone()))
+        .chain(table.foreign_keys.iter().cloned().map(Column::ForeignKey))
+        .chain(table.data_columns.iter().cloned().map(Column::Data))
+        .chain(
+            table
+                .timestamp_columns
+                .iter()
+                .cloned()
+                .map(Column::Timestamp),
+        )
+        .chain(table.version_column.iter().cloned().map(Column::Version))
+        .collect();
+
+    columns.sort_by_key(|c| c.name().field().to_string());
+
+    let mut fields = TokenStream::new();
+    let mut values = TokenStream::new();
+
+    for column in columns {
+        let field = column.name().field();
+        let ty = column.ty();
+        let quoted = column.quote();
+
+        let doc = format!(
+            "The column of `{}`",
+            field.to_string().trim_start_matches("r#")
+        );
+
+        fields.extend(quote!(
+            #[doc = #doc]
+            #vis #field: ::atmosphere::TypedColumn<#ident, #ty>,
+        ));
+
+        values.extend(quote!(
+            #field: ::atmosphere::TypedColumn::new(#quoted.as_col()),
+        ));
+    }
+
+    let doc = format!("The typed columns of [`{ident}`], returned by `{ident}::col()`.");
+
+    // the columns are returned by a method instead of being associated constants of the table,
+    // so that they can not shadow the constants of `Table` (e.g. of fields named `table`)
+    quote!(
+        #[doc = #doc]
+        #vis struct #columns_ident {
+            #fields
+        }
+
+        #[automatically_derived]
+        impl #ident {
+            /// The typed columns of the table
+            #vis const fn col() -> #columns_ident {
+                const COLUMNS: #columns_ident = #columns_ident {
+                    #values
+                };
+
+                COLUMNS
+            }
+        }
+    )
+}
@@ -0,0 +1,82 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::schema::column::Column;
+use crate::schema::table::Table;
+
+pub fn ddl(table: &Table) -> TokenStream {
+    let ident = &table.ident;
+
+    let mut foreign_keys: Vec<_> = table.foreign_keys.iter().collect();
+    let mut data: Vec<_> = table.data_columns.iter().collect();
+    let mut timestamps: Vec<_> = table.timestamp_columns.iter().collect();
+
+    foreign_keys.sort_by_key(|fk| fk.name.field().to_string());
+    data.sort_by_key(|data| data.name.field().to_string());
+    timestamps.sort_by_key(|ts| ts.name.field().to_string());
+
+    let columns: Vec<Column> = std::iter::once(Column::PrimaryKey(table.primary_key.clone()))
+        .chain(foreign_keys.into_iter().cloned().map(Column::ForeignKey))
+        .chain(data.into_iter().cloned().map(Column::Data))
+        .chain(table.version_column.iter().cloned().map(Column::Version))
+        .chain(timestamps.into_iter().cloned().map(Column::Timestamp))
+        .collect();
+
+    let mut definitions = vec![];
+    let mut bounds: Vec<String> = vec![];
+    let mut where_clause = TokenStream::new();
+
+    for column in &columns {
+        let ty = column.ty();
+        let quoted = column.quote();
+
+        let (unique, generated) = match column {
+            Column::PrimaryKey(pk) => (true, pk.modifiers.generated),
+            Column::ForeignKey(fk) => (fk.modifiers.unique, false),
+            Column::Data(data) => (data.modifiers.unique, false),
+            Column::Timestamp(ts) => (ts.modifiers.unique, false),
+            Column::Version(v) => (v.modifiers.unique, false),
+        };
+
+        let references = match column {
+            Column::ForeignKey(fk) => {
+                let on = &fk.on;
+                quote!(Some(::atmosphere::Reference::to::<#on>()))
+            }
+            _ => quote!(None),
+        };
+
+        definitions.push(quote!(
+            ::atmosphere::ColumnDefinition {
+                column: #quoted.as_col(),
+                ty: <#ty as ::atmosphere::SqlType>::SQL,
+                nullable: <#ty as ::atmosphere::SqlType>::NULLABLE,
+                unique: #unique,
+                generated: #generated,
+                references: #references,
+            }
+        ));
+
+        let bound = quote!(#ty).to_string();
+
+        if !bounds.contains(&bound) {
+            bounds.push(bound);
+
+            // the higher-ranked bound defers the check to uses of the implementation, so that
+            // tables with field types without sql type still derive `Schema`
+            where_clause.extend(quote!(for<'a> #ty: ::atmosphere::SqlType,));
+        }
+    }
+
+    quote!(
+        #[automatically_derived]
+        impl ::atmosphere::Ddl for #ident
+        where
+            #where_clause
+        {
+            const COLUMN_DEFINITIONS: &'static [::atmosphere::ColumnDefinition<#ident>] = &[
+                #(#definitions),*
+            ];
+        }
+    )
+}
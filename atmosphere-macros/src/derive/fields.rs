@@ -0,0 +1,113 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Ident, Type};
+
+use crate::schema::table::Table;
+
+/// A column of a table which is copied into a generated struct, such as patches and insertables.
+pub struct Field<'t> {
+    pub name: &'t Ident,
+    pub ty: &'t Type,
+    kind: TokenStream,
+    consts: TokenStream,
+}
+
+impl Field<'_> {
+    /// Resolves the column of the field from the column constants of `table`.
+    pub fn column(&self, table: &Ident) -> TokenStream {
+        let Self {
+            name, kind, consts, ..
+        } = self;
+
+        quote!(
+            ::atmosphere::Column::#kind(
+                <#table as ::atmosphere::Table>::#consts
+                    .iter()
+                    .find(|c| c.field == stringify!(#name))
+                    .expect("column is known")
+            )
+        )
+    }
+}
+
+/// Collects the foreign key and data columns of `table` and, if `timestamps` is set, its
+/// timestamp columns, sorted by their fields.
+pub fn fields(table: &Table, timestamps: bool) -> Vec<Field<'_>> {
+    let timestamps = table
+        .timestamp_columns
+        .iter()
+        .filter(|_| timestamps)
+        .map(|ts| Field {
+            name: ts.name.field(),
+            ty: &ts.ty,
+            kind: quote!(Timestamp),
+            consts: quote!(TIMESTAMP_COLUMNS),
+        });
+
+    let mut fields: Vec<_> = table
+        .foreign_keys
+        .iter()
+        .map(|fk| Field {
+            name: fk.name.field(),
+            ty: &fk.ty,
+            kind: quote!(ForeignKey),
+            consts: quote!(FOREIGN_KEYS),
+        })
+        .chain(table.data_columns.iter().map(|data| Field {
+            name: data.name.field(),
+            ty: &data.ty,
+            kind: quote!(Data),
+            consts: quote!(DATA_COLUMNS),
+        }))
+        .chain(timestamps)
+        .collect();
+
+    fields.sort_by_key(|f| f.name.to_string());
+
+    fields
+}
+
+fn col() -> Ident {
+    Ident::new("col", Span::call_site())
+}
+
+fn query() -> Ident {
+    Ident::new("query", Span::call_site())
+}
+
+/// Binds `value` if the bound column is the column of `field`.
+pub fn bind_field(field: &Ident, value: TokenStream) -> TokenStream {
+    let col = col();
+    let query = query();
+
+    quote!(
+        if #col.field() == stringify!(#field) {
+            use ::atmosphere::Bindable;
+            return Ok(#query.dyn_bind(#value));
+        }
+    )
+}
+
+/// Generates the `bind` method of a struct generated for `table`, trying `binds` (see
+/// [`bind_field`]) in order and failing for all other columns.
+pub fn bind(table: &Ident, binds: TokenStream) -> TokenStream {
+    let col = col();
+    let query = query();
+
+    quote!(
+        fn bind<
+            'q,
+            Q: ::atmosphere::Bindable<'q>
+        >(
+            &'q self,
+            #col: &'q ::atmosphere::Column<#table>,
+            #query: Q
+        ) -> ::atmosphere::Result<Q> {
+            #binds
+
+            Err(::atmosphere::Error::Bind(
+                ::atmosphere::bind::BindError::Unknown(#col.field())
+            ))
+        }
+    )
+}
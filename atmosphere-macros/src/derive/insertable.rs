@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use super::fields;
+use crate::schema::table::Table;
+
+pub fn insertable(table: &Table) -> TokenStream {
+    let Some(attrs) = &table.insertable else {
+        return TokenStream::new();
+    };
+
+    let Table { vis, ident, .. } = table;
+
+    let insertable = Ident::new(&format!("New{ident}"), ident.span());
+
+    let mut fields = TokenStream::new();
+    let mut provided = TokenStream::new();
+    let mut binds = TokenStream::new();
+
+    let pk = &table.primary_key;
+
+    if !pk.modifiers.generated {
+        let field = pk.name.field();
+        let ty = &pk.ty;
+
+        fields.extend(quote!(
+            #vis #field: #ty,
+        ));
+
+        provided.extend(quote!(
+            ::atmosphere::Column::PrimaryKey(&<#ident as ::atmosphere::Table>::PRIMARY_KEY),
+        ));
+
+        binds.extend(fields::bind_field(field, quote!(&self.#field)));
+    }
+
+    for field in fields::fields(table, false) {
+        let name = field.name;
+        let ty = field.ty;
+        let column = field.column(ident);
+
+        fields.extend(quote!(
+            #vis #name: #ty,
+        ));
+
+        provided.extend(quote!(
+            #column,
+        ));
+
+        binds.extend(fields::bind_field(name, quote!(&self.#name)));
+    }
+
+    let bind = fields::bind(ident, binds);
+
+    let doc = format!("A new [`{ident}`], inserted using `insert`.");
+
+    quote!(
+        #[doc = #doc]
+        #(#[#attrs])*
+        #vis struct #insertable {
+            #fields
+        }
+
+        #[automatically_derived]
+        impl ::atmosphere::Insertable for #insertable {
+            type Table = #ident;
+
+            fn columns() -> Vec<::atmosphere::Column<#ident>> {
+                vec![#provided]
+            }
+
+            #bind
+        }
+    )
+}
@@ -3,17 +3,31 @@ use quote::quote;
 
 use crate::schema::table::Table;
 
+pub use projection::projection;
+
+mod aliased;
 mod bindings;
+mod columns;
+mod ddl;
+mod fields;
 mod hooks;
+mod insertable;
+mod patch;
+mod projection;
 mod queries;
 mod relationships;
 mod table;
 
 pub fn all(table: &Table) -> TokenStream {
+    let aliased = aliased::aliased(table);
     let bindings = bindings::bindings(table);
+    let columns = columns::columns(table);
+    let ddl = ddl::ddl(table);
     let queries = queries::queries(table);
     let relationships = relationships::relationships(table);
     let hooks = hooks::hooks(table);
+    let patch = patch::patch(table);
+    let insertable = insertable::insertable(table);
     let table = table::table(table);
 
     quote!(
@@ -21,10 +35,20 @@ pub fn all(table: &Table) -> TokenStream {
 
         #bindings
 
+        #columns
+
+        #ddl
+
+        #aliased
+
         #queries
 
         #relationships
 
         #hooks
+
+        #patch
+
+        #insertable
     )
 }
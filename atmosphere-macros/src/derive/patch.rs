@@ -0,0 +1,71 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use super::fields;
+use crate::schema::table::Table;
+
+pub fn patch(table: &Table) -> TokenStream {
+    let Some(attrs) = &table.patch else {
+        return TokenStream::new();
+    };
+
+    let Table { vis, ident, .. } = table;
+
+    let patch = Ident::new(&format!("{ident}Patch"), ident.span());
+
+    let mut fields = TokenStream::new();
+    let mut provided = TokenStream::new();
+    let mut binds = TokenStream::new();
+
+    for field in fields::fields(table, true) {
+        let name = field.name;
+        let ty = field.ty;
+        let column = field.column(ident);
+        let bind = fields::bind_field(name, quote!(value));
+
+        fields.extend(quote!(
+            #vis #name: Option<#ty>,
+        ));
+
+        provided.extend(quote!(
+            if self.#name.is_some() {
+                columns.push(#column);
+            }
+        ));
+
+        binds.extend(quote!(
+            if let Some(value) = &self.#name {
+                #bind
+            }
+        ));
+    }
+
+    let bind = fields::bind(ident, binds);
+
+    let doc = format!("A partial update of [`{ident}`], applied using `apply_patch`.");
+
+    quote!(
+        #[doc = #doc]
+        #[derive(Default)]
+        #(#[#attrs])*
+        #vis struct #patch {
+            #fields
+        }
+
+        #[automatically_derived]
+        impl ::atmosphere::Patch for #patch {
+            type Table = #ident;
+
+            fn columns(&self) -> Vec<::atmosphere::Column<#ident>> {
+                let mut columns = vec![];
+
+                #provided
+
+                columns
+            }
+
+            #bind
+        }
+    )
+}
@@ -0,0 +1,110 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Path, Token};
+
+/// The table of a projection, set by `#[projection(of = Table)]`
+struct Of(Path);
+
+impl Parse for Of {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+
+        if key != "of" {
+            return Err(Error::new_spanned(
+                key,
+                "`#[projection]` only supports the value `of`",
+            ));
+        }
+
+        input.parse::<Token![=]>()?;
+
+        Ok(Self(input.parse()?))
+    }
+}
+
+pub fn projection(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "projections can not be generic",
+        ));
+    }
+
+    let attribute = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("projection"))
+        .ok_or_else(|| {
+            Error::new(
+                Span::call_site(),
+                "`#[derive(Projection)]` requires `#[projection(of = Table)]`",
+            )
+        })?;
+
+    let Of(table) = attribute.parse_args()?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "only structs with named fields can be projections",
+                ))
+            }
+        },
+        _ => return Err(Error::new_spanned(ident, "only structs can be projections")),
+    };
+
+    let mut checks = TokenStream::new();
+    let mut columns = TokenStream::new();
+    let mut decode = TokenStream::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let name = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+
+        // fails to compile if the table has no column of this name and type
+        checks.extend(quote_spanned!(field.span() =>
+            const _: ::atmosphere::TypedColumn<#table, #ty> = <#table>::col().#name;
+        ));
+
+        columns.extend(quote!(
+            <#table>::col().#name.column().clone(),
+        ));
+
+        decode.extend(quote!(
+            #name: row.try_get(#index)?,
+        ));
+    }
+
+    Ok(quote!(
+        #checks
+
+        #[automatically_derived]
+        impl<'r> ::atmosphere::sqlx::FromRow<'r, <::atmosphere::Driver as ::atmosphere::sqlx::Database>::Row> for #ident {
+            fn from_row(
+                row: &'r <::atmosphere::Driver as ::atmosphere::sqlx::Database>::Row
+            ) -> ::std::result::Result<Self, ::atmosphere::sqlx::Error> {
+                use ::atmosphere::sqlx::Row;
+
+                Ok(Self {
+                    #decode
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl ::atmosphere::Projection for #ident {
+            type Table = #table;
+
+            fn columns() -> Vec<::atmosphere::Column<#table>> {
+                vec![#columns]
+            }
+        }
+    ))
+}
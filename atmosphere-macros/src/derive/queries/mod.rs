@@ -1,9 +1,18 @@
 use proc_macro2::TokenStream;
+use quote::quote;
 
 use crate::schema::table::Table;
 
+mod search;
 mod unique;
 
 pub fn queries(table: &Table) -> TokenStream {
-    unique::queries(table)
+    let unique = unique::queries(table);
+    let search = search::queries(table);
+
+    quote!(
+        #unique
+
+        #search
+    )
 }
@@ -0,0 +1,44 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::schema::table::Table;
+
+pub fn queries(table: &Table) -> TokenStream {
+    let ident = &table.ident;
+
+    let mut columns: Vec<_> = table
+        .data_columns
+        .iter()
+        .filter(|data| data.modifiers.searchable)
+        .collect();
+
+    let config = &table.id.search_config;
+
+    if columns.is_empty() {
+        return match config {
+            Some(config) => syn::Error::new_spanned(
+                config,
+                "`search_config` requires columns marked as `#[sql(searchable)]`",
+            )
+            .to_compile_error(),
+            None => TokenStream::new(),
+        };
+    }
+
+    columns.sort_by_key(|data| data.name.field().to_string());
+
+    let columns = columns.iter().map(|data| data.quote());
+
+    let config = config
+        .as_ref()
+        .map(|config| quote!(const CONFIG: &'static str = #config;));
+
+    quote!(
+        #[automatically_derived]
+        impl ::atmosphere::Search for #ident {
+            const SEARCH_COLUMNS: &'static [::atmosphere::DataColumn<#ident>] = &[#(#columns),*];
+
+            #config
+        }
+    )
+}
@@ -31,7 +31,9 @@ pub fn queries(table: &Table) -> TokenStream {
         let column = column.quote();
 
         let find_by_col = Ident::new(&format!("find_by_{col}"), Span::mixed_site());
+        let exists_by_col = Ident::new(&format!("exists_by_{col}"), Span::mixed_site());
         let delete_by_col = Ident::new(&format!("delete_by_{col}"), Span::mixed_site());
+        let on_conflict_col = Ident::new(&format!("on_conflict_{col}"), Span::mixed_site());
 
         stream.extend(quote!(
             #[automatically_derived]
@@ -57,10 +59,37 @@ pub fn queries(table: &Table) -> TokenStream {
 
                     ::atmosphere::sqlx::query_as(query.sql())
                         .bind(value)
-                        .persistent(false)
                         .fetch_optional(executor)
                         .await
-                        .map_err(QueryError::from)
+                        .map_err(|e| QueryError::from(e).within::<#ident>(query.op))
+                        .map_err(Error::Query)
+                }
+
+                pub async fn #exists_by_col<'e, E>(
+                    value: &#ty,
+                    executor: E
+                ) -> ::atmosphere::Result<bool>
+                where
+                    E: ::atmosphere::sqlx::Executor<'e, Database = ::atmosphere::Driver>,
+                    for<'q> <::atmosphere::Driver as ::atmosphere::sqlx::database::HasArguments<'q>>::Arguments:
+                        ::atmosphere::sqlx::IntoArguments<'q, ::atmosphere::Driver> + Send
+                {
+                    use ::atmosphere::{
+                        query::{Query, QueryError},
+                        runtime::sql,
+                        Error
+                    };
+
+                    const COLUMN: ::atmosphere::Column<#ident> = #column.as_col();
+
+                    let query = sql::exists_by::<#ident>(COLUMN.clone());
+
+                    ::atmosphere::sqlx::query(query.sql())
+                        .bind(value)
+                        .fetch_optional(executor)
+                        .await
+                        .map(|row| row.is_some())
+                        .map_err(|e| QueryError::from(e).within::<#ident>(query.op))
                         .map_err(Error::Query)
                 }
 
@@ -85,12 +114,17 @@ pub fn queries(table: &Table) -> TokenStream {
 
                     ::atmosphere::sqlx::query(query.sql())
                         .bind(value)
-                        .persistent(false)
                         .execute(executor)
                         .await
-                        .map_err(QueryError::from)
+                        .map_err(|e| QueryError::from(e).within::<#ident>(query.op))
                         .map_err(Error::Query)
                 }
+
+                pub const fn #on_conflict_col() -> ::atmosphere::Upsert<#ident> {
+                    const COLUMN: ::atmosphere::Column<#ident> = #column.as_col();
+
+                    ::atmosphere::Upsert::on(COLUMN)
+                }
             }
         ))
     }
@@ -29,6 +29,16 @@ pub fn relationships(table: &Table) -> TokenStream {
             Span::mixed_site(),
         );
 
+        let join_other = Ident::new(
+            &format!("join_{}", fk.name.field().to_string().to_lowercase()),
+            Span::mixed_site(),
+        );
+
+        let join_all_self = Ident::new(
+            &format!("join_{}s", ident.to_string().to_lowercase()),
+            Span::mixed_site(),
+        );
+
         let delete_self = Ident::new(
             &format!("delete_{}s", ident.to_string().to_lowercase()),
             Span::mixed_site(),
@@ -59,6 +69,17 @@ pub fn relationships(table: &Table) -> TokenStream {
                         ::atmosphere::sqlx::IntoArguments<'q, ::atmosphere::Driver> + Send {
                     <#other as ::atmosphere::rel::ReferredBy<#ident>>::resolve_by(pk, executor).await
                 }
+
+                pub async fn #join_other<'e, E>(
+                    filter: &::atmosphere::filter::Filter<#ident>,
+                    executor: E,
+                ) -> ::atmosphere::Result<Vec<(#ident, #other)>>
+                where
+                    E: ::atmosphere::sqlx::Executor<'e, Database = ::atmosphere::Driver>,
+                    for<'q> <::atmosphere::Driver as ::atmosphere::sqlx::database::HasArguments<'q>>::Arguments:
+                        ::atmosphere::sqlx::IntoArguments<'q, ::atmosphere::Driver> + Send {
+                    <#ident as ::atmosphere::rel::RefersTo<#other>>::join(filter, executor).await
+                }
             }
 
             #[automatically_derived]
@@ -74,6 +95,17 @@ pub fn relationships(table: &Table) -> TokenStream {
                     <#other as ::atmosphere::rel::ReferredBy<#ident>>::resolve(&self, executor).await
                 }
 
+                pub async fn #join_all_self<'e, E>(
+                    filter: &::atmosphere::filter::Filter<#other>,
+                    executor: E,
+                ) -> ::atmosphere::Result<Vec<(#other, Option<#ident>)>>
+                where
+                    E: ::atmosphere::sqlx::Executor<'e, Database = ::atmosphere::Driver>,
+                    for<'q> <::atmosphere::Driver as ::atmosphere::sqlx::database::HasArguments<'q>>::Arguments:
+                        ::atmosphere::sqlx::IntoArguments<'q, ::atmosphere::Driver> + Send {
+                    <#other as ::atmosphere::rel::ReferredBy<#ident>>::left_join(filter, executor).await
+                }
+
                 pub async fn #delete_self<'e, E>(
                     &self,
                     executor: E,
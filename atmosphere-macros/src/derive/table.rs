@@ -11,6 +11,7 @@ pub fn table(table: &Table) -> TokenStream {
         foreign_keys,
         data_columns,
         timestamp_columns,
+        version_column,
         ..
     } = table;
 
@@ -25,6 +26,22 @@ pub fn table(table: &Table) -> TokenStream {
     let data = data_columns.iter().map(|d| d.quote());
     let timestamps = timestamp_columns.iter().map(|d| d.quote());
 
+    let version = match version_column {
+        Some(version) => {
+            let column = version.quote();
+            let field = version.name.field();
+
+            quote!(
+                const VERSION_COLUMN: Option<::atmosphere::VersionColumn<#ident>> = Some(#column);
+
+                fn increment_version(&mut self) {
+                    self.#field += 1;
+                }
+            )
+        }
+        None => TokenStream::new(),
+    };
+
     quote!(
         #[automatically_derived]
         impl ::atmosphere::Table for #ident {
@@ -41,6 +58,8 @@ pub fn table(table: &Table) -> TokenStream {
             fn pk(&self) -> &Self::PrimaryKey {
                 &self.#pk_field
             }
+
+            #version
         }
     )
 }
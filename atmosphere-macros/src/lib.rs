@@ -11,7 +11,8 @@
 
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{parse_macro_input, ItemStruct};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, DeriveInput, ItemStruct, Meta, Token};
 
 mod derive;
 mod hooks;
@@ -26,15 +27,33 @@ use schema::table::Table;
 /// Entity attributes:
 ///
 /// - `#[table(schema = "schema_name", name = "table_name")]` - Set schema and table name
+/// - `#[table(.., search_config = "german")]` - Set the text search configuration of full-text
+///   searches in postgres (see `Search`)
+/// - `#[patch(..)]` - Generate a patch struct for partial updates (see [`macro@patch`])
+/// - `#[insertable(..)]` - Generate a struct for inserting new rows (see [`macro@insertable`])
 ///
 /// Field attributes:
 ///
 /// - `#[sql(pk)]` - Mark a column as primary key
+/// - `#[sql(pk, generated)]` - Mark a column as primary key which is assigned by the database
 /// - `#[sql(fk -> OtherModel)]` - Mark a column as foreign key on `OtherModel`
 /// - `#[sql(unique)]` - Mark a column as unique
+/// - `#[sql(searchable)]` - Mark a text column as searchable using full-text search (see
+///   `Search`)
 /// - `#[sql(timestamp = [create|update|delete])]` - Mark a column as timestamp
+/// - `#[sql(version)]` - Mark an integer column as version for optimistic concurrency control
 /// - `#[sql(.., rename = "renamed_sql_col")]` - Rename a column in the generated sql
 ///
+/// Every field is also exposed as a typed column, returned by `User::col()` (e.g.
+/// `User::col().username`), which is used by queries over columns such as aggregates.
+///
+/// Foreign keys generate methods to resolve and join the related entities, e.g. `post.author(..)`,
+/// `Post::join_author(..)` returning `(Post, User)` pairs and `User::join_posts(..)` returning
+/// `(User, Option<Post>)` pairs.
+///
+/// If the sql types of all field types are known (see `SqlType`), `Ddl` is implemented, which
+/// generates the table's `CREATE TABLE` and `DROP TABLE` statements: `User::create_table_sql()`.
+///
 /// Usage:
 ///
 /// ```ignore
@@ -60,9 +79,56 @@ use schema::table::Table;
 #[proc_macro_derive(Schema, attributes(sql))]
 pub fn schema(input: TokenStream) -> TokenStream {
     let table = parse_macro_input!(input as Table);
+
+    if !table.generics.params.is_empty() {
+        return syn::Error::new_spanned(&table.generics, "tables can not be generic")
+            .to_compile_error()
+            .into();
+    }
+
     derive::all(&table).into()
 }
 
+/// A derive macro for projections, structs holding a subset of the columns of a table. The
+/// table is set using `#[projection(of = Table)]`.
+///
+/// Every field must be named like a field of the table and have the same type, which is checked
+/// at compile time. Projections implement `Projection` and only select their own columns.
+///
+/// Usage:
+///
+/// ```ignore
+/// # use atmosphere::prelude::*;
+/// # #[derive(Schema)]
+/// # #[table(schema = "public", name = "user")]
+/// # struct User {
+/// #     #[sql(pk)]
+/// #     id: i32,
+/// #     #[sql(unique)]
+/// #     username: String,
+/// #     biography: String,
+/// # }
+/// #[derive(Projection)]
+/// #[projection(of = User)]
+/// struct Username {
+///     id: i32,
+///     username: String,
+/// }
+///
+/// # async fn run(pool: &atmosphere::Pool) -> atmosphere::Result<()> {
+/// let user = Username::find(&1, pool).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_derive(Projection, attributes(projection))]
+pub fn projection(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive::projection(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// An attribute macro that stores metadata about the sql table.
 /// Must be used after `#[derive(Schema)]`.
 ///
@@ -70,6 +136,7 @@ pub fn schema(input: TokenStream) -> TokenStream {
 ///
 /// - `schema` - sets schema name.
 /// - `name` - sets table name.
+/// - `search_config` - sets the text search configuration of full-text searches (optional).
 ///
 /// Usage:
 ///
@@ -166,3 +233,79 @@ pub fn hooks(attr: TokenStream, input: TokenStream) -> TokenStream {
     let _ = parse_macro_input!(attr as hooks::Hooks);
     quote! { #model }.into()
 }
+
+/// An attribute macro for generating a patch struct for partial updates. Must be used after
+/// `#[derive(Schema)]`.
+///
+/// The generated struct is named after the table with a `Patch` suffix and holds an `Option` of
+/// every column except the primary key. It implements `Patch` and `Default` and is applied using
+/// `Update::apply_patch`, which only sets the provided columns. Arguments are forwarded to the
+/// generated struct as attributes.
+///
+/// Usage:
+///
+/// ```ignore
+/// # use atmosphere::prelude::*;
+/// #[derive(Schema)]
+/// #[table(schema = "public", name = "user")]
+/// #[patch(derive(Debug, serde::Deserialize))]
+/// struct User {
+///     #[sql(pk)]
+///     id: i32,
+///     #[sql(unique)]
+///     username: String,
+/// }
+///
+/// # async fn run(pool: &atmosphere::Pool) -> atmosphere::Result<()> {
+/// let patch = UserPatch {
+///     username: Some("jane".to_owned()),
+/// };
+///
+/// User::apply_patch(&1, &patch, pool).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn patch(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let model = parse_macro_input!(input as ItemStruct);
+    let _ = parse_macro_input!(attr with Punctuated::<Meta, Token![,]>::parse_terminated);
+    quote! { #model }.into()
+}
+
+/// An attribute macro for generating a struct for inserting new rows. Must be used after
+/// `#[derive(Schema)]`.
+///
+/// The generated struct is named after the table with a `New` prefix and holds every column
+/// except for generated primary keys (`#[sql(pk, generated)]`), timestamps and the version,
+/// which are assigned by the database. It implements `Insertable`, whose `insert` method returns
+/// the inserted row. Arguments are forwarded to the generated struct as attributes.
+///
+/// Usage:
+///
+/// ```ignore
+/// # use atmosphere::prelude::*;
+/// #[derive(Schema)]
+/// #[table(schema = "public", name = "user")]
+/// #[insertable(derive(Debug, serde::Deserialize))]
+/// struct User {
+///     #[sql(pk, generated)]
+///     id: i32,
+///     #[sql(unique)]
+///     username: String,
+/// }
+///
+/// # async fn run(pool: &atmosphere::Pool) -> atmosphere::Result<()> {
+/// let user: User = NewUser {
+///     username: "jane".to_owned(),
+/// }
+/// .insert(pool)
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn insertable(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let model = parse_macro_input!(input as ItemStruct);
+    let _ = parse_macro_input!(attr with Punctuated::<Meta, Token![,]>::parse_terminated);
+    quote! { #model }.into()
+}
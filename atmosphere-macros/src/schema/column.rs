@@ -29,6 +29,8 @@ impl NameSet {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ColumnModifiers {
     pub unique: bool,
+    pub generated: bool,
+    pub searchable: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -91,12 +93,32 @@ impl DataColumn {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionColumn {
+    pub modifiers: ColumnModifiers,
+    pub name: NameSet,
+    pub ty: Type,
+}
+
+impl VersionColumn {
+    pub fn quote(&self) -> TokenStream {
+        let field = self.name.field();
+        let sql = self.name.sql();
+
+        quote!(::atmosphere::VersionColumn::new(
+            stringify!(#field),
+            stringify!(#sql)
+        ))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Column {
     PrimaryKey(PrimaryKey),
     ForeignKey(ForeignKey),
     Data(DataColumn),
     Timestamp(TimestampColumn),
+    Version(VersionColumn),
 }
 
 impl Hash for Column {
@@ -112,6 +134,7 @@ impl Column {
             Self::ForeignKey(fk) => fk.quote(),
             Self::Data(data) => data.quote(),
             Self::Timestamp(time) => time.quote(),
+            Self::Version(version) => version.quote(),
         }
     }
 
@@ -121,6 +144,7 @@ impl Column {
             Self::ForeignKey(fk) => &fk.ty,
             Self::Data(data) => &data.ty,
             Self::Timestamp(ts) => &ts.ty,
+            Self::Version(v) => &v.ty,
         }
     }
 }
@@ -135,7 +159,10 @@ pub mod attribute {
     const PRIMARY_KEY: &str = "pk";
     const FOREIGN_KEY: &str = "fk";
     const UNIQUE: &str = "unique";
+    const GENERATED: &str = "generated";
+    const SEARCHABLE: &str = "searchable";
     const TIMESTAMP: &str = "timestamp";
+    const VERSION: &str = "version";
 
     const TIMESTAMP_CREATED: &str = "created";
     const TIMESTAMP_UPDATED: &str = "updated";
@@ -147,6 +174,7 @@ pub mod attribute {
         ForeignKey { on: Ident },
         Data,
         Timestamp { kind: TimestampKind },
+        Version,
     }
 
     impl Parse for ColumnKind {
@@ -191,6 +219,11 @@ pub mod attribute {
 
                         kind = ColumnKind::Timestamp { kind: ty }
                     }
+                    VERSION => {
+                        let _: Ident = input.parse()?;
+
+                        kind = ColumnKind::Version;
+                    }
                     _ => {}
                 };
 
@@ -214,12 +247,68 @@ pub mod attribute {
         fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
             let kind: ColumnKind = input.parse()?;
 
-            let mut modifiers = ColumnModifiers { unique: false };
+            let mut modifiers = ColumnModifiers {
+                unique: false,
+                generated: false,
+                searchable: false,
+            };
             let mut renamed = None;
 
             while !input.is_empty() {
                 let ident: syn::Ident = input.parse()?;
 
+                if ident.to_string().as_str() == GENERATED {
+                    if kind != ColumnKind::PrimaryKey {
+                        return Err(Error::new(
+                            ident.span(),
+                            "only primary keys can be marked as `generated`",
+                        ));
+                    }
+
+                    if modifiers.generated {
+                        return Err(Error::new(
+                            ident.span(),
+                            "found redundant `generated` modifier",
+                        ));
+                    }
+
+                    modifiers.generated = true;
+
+                    if !input.peek(Token![,]) {
+                        break;
+                    }
+
+                    input.parse::<Token![,]>()?;
+
+                    continue;
+                }
+
+                if ident.to_string().as_str() == SEARCHABLE {
+                    if kind != ColumnKind::Data {
+                        return Err(Error::new(
+                            ident.span(),
+                            "only data columns can be marked as `searchable`",
+                        ));
+                    }
+
+                    if modifiers.searchable {
+                        return Err(Error::new(
+                            ident.span(),
+                            "found redundant `searchable` modifier",
+                        ));
+                    }
+
+                    modifiers.searchable = true;
+
+                    if !input.peek(Token![,]) {
+                        break;
+                    }
+
+                    input.parse::<Token![,]>()?;
+
+                    continue;
+                }
+
                 // we found a tag
                 if ident.to_string().as_str() == UNIQUE {
                     if modifiers.unique {
@@ -283,7 +372,11 @@ impl TryFrom<Field> for Column {
 
         let Some(attribute) = attribute else {
             return Ok(Self::Data(DataColumn {
-                modifiers: ColumnModifiers { unique: false },
+                modifiers: ColumnModifiers {
+                    unique: false,
+                    generated: false,
+                    searchable: false,
+                },
                 name: NameSet::new(name, None),
                 ty,
             }));
@@ -296,7 +389,10 @@ impl TryFrom<Field> for Column {
 
         match attribute.kind {
             attribute::ColumnKind::PrimaryKey => Ok(Self::PrimaryKey(PrimaryKey {
-                modifiers: ColumnModifiers { unique: true },
+                modifiers: ColumnModifiers {
+                    unique: true,
+                    ..modifiers
+                },
                 name,
                 ty,
             })),
@@ -317,6 +413,11 @@ impl TryFrom<Field> for Column {
                 name,
                 ty,
             })),
+            attribute::ColumnKind::Version => Ok(Self::Version(VersionColumn {
+                modifiers,
+                name,
+                ty,
+            })),
         }
     }
 }
@@ -328,6 +429,7 @@ impl Column {
             Self::ForeignKey(fk) => &fk.name,
             Self::Data(data) => &data.name,
             Self::Timestamp(ts) => &ts.name,
+            Self::Version(v) => &v.name,
         }
     }
 }
@@ -361,4 +463,11 @@ impl Column {
             _ => None,
         }
     }
+
+    pub const fn as_version_column(&self) -> Option<&VersionColumn> {
+        match self {
+            Self::Version(c) => Some(c),
+            _ => None,
+        }
+    }
 }
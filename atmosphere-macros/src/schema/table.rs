@@ -1,22 +1,26 @@
 use std::collections::HashSet;
 
 use syn::parse::{Parse, ParseStream};
-use syn::{Error, Fields, Generics, Ident, LitStr, Token, Visibility};
+use syn::punctuated::Punctuated;
+use syn::{Error, Fields, Generics, Ident, LitStr, Meta, Token, Visibility};
 
 use crate::hooks::Hooks;
-use crate::schema::column::{Column, DataColumn, TimestampColumn};
+use crate::schema::column::{Column, DataColumn, TimestampColumn, VersionColumn};
 use crate::schema::keys::{ForeignKey, PrimaryKey};
 
 #[derive(Clone, Debug)]
 pub struct TableId {
     pub schema: String,
     pub table: String,
+    /// The text search configuration of full-text searches, if set by `search_config`
+    pub search_config: Option<LitStr>,
 }
 
 impl Parse for TableId {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut schema = None;
         let mut table = None;
+        let mut search_config = None;
 
         while !input.is_empty() {
             let ident: syn::Ident = input.parse()?;
@@ -26,10 +30,11 @@ impl Parse for TableId {
             match ident.to_string().as_str() {
                 "schema" => schema = Some(value.value()),
                 "name" => table = Some(value.value()),
+                "search_config" => search_config = Some(value),
                 _ => {
                     return Err(syn::Error::new_spanned(
                         ident,
-                        "`#[table]` supports only the values `schema` and `name`",
+                        "`#[table]` supports only the values `schema`, `name` and `search_config`",
                     ))
                 }
             }
@@ -49,7 +54,11 @@ impl Parse for TableId {
             syn::Error::new(input.span(), "`#[table]` requires a value for `name`")
         })?;
 
-        Ok(Self { schema, table })
+        Ok(Self {
+            schema,
+            table,
+            search_config,
+        })
     }
 }
 
@@ -65,8 +74,29 @@ pub struct Table {
     pub foreign_keys: HashSet<ForeignKey>,
     pub data_columns: HashSet<DataColumn>,
     pub timestamp_columns: HashSet<TimestampColumn>,
+    pub version_column: Option<VersionColumn>,
 
     pub hooks: Hooks,
+
+    /// The attributes of the generated patch struct, if `#[patch]` is used
+    pub patch: Option<Vec<Meta>>,
+    /// The attributes of the generated insertable struct, if `#[insertable]` is used
+    pub insertable: Option<Vec<Meta>>,
+}
+
+/// Parses the attributes forwarded to a generated struct by an (optional) attribute like
+/// `#[patch(derive(Debug))]`.
+fn forwarded(attrs: &[syn::Attribute], name: &str) -> syn::Result<Option<Vec<Meta>>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .map(|attr| match attr.meta {
+            Meta::Path(_) => Ok(vec![]),
+            _ => attr
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .map(|attrs| attrs.into_iter().collect()),
+        })
+        .transpose()
 }
 
 impl Parse for Table {
@@ -93,6 +123,9 @@ impl Parse for Table {
             }
         };
 
+        let patch = forwarded(&item.attrs, "patch")?;
+        let insertable = forwarded(&item.attrs, "insertable")?;
+
         let ident = item.ident;
 
         let fields = match item.fields {
@@ -158,6 +191,26 @@ impl Parse for Table {
             .cloned()
             .collect();
 
+        let version_column = {
+            let version_columns: Vec<VersionColumn> = columns
+                .iter()
+                .filter_map(|c| c.as_version_column())
+                .cloned()
+                .collect();
+
+            if version_columns.len() > 1 {
+                return Err(Error::new(
+                    input.span(),
+                    format!(
+                        "{} declares more than one column as its version – only one is allowed",
+                        ident
+                    ),
+                ));
+            }
+
+            version_columns.into_iter().next()
+        };
+
         Ok(Self {
             vis: item.vis,
             generics: item.generics,
@@ -167,7 +220,10 @@ impl Parse for Table {
             foreign_keys,
             data_columns,
             timestamp_columns,
+            version_column,
             hooks,
+            patch,
+            insertable,
         })
     }
 }
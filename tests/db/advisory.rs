@@ -0,0 +1,83 @@
+use atmosphere::advisory::{self, AdvisoryLock};
+use atmosphere::prelude::*;
+use atmosphere::transaction::Transaction;
+
+use super::fixtures::{Forest, Tree};
+
+#[test]
+fn key() {
+    assert_eq!(advisory::key::<Forest>(&1), advisory::key::<Forest>(&1));
+    assert_ne!(advisory::key::<Forest>(&1), advisory::key::<Forest>(&2));
+    assert_ne!(advisory::key::<Forest>(&1), advisory::key::<Tree>(&1));
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn session(pool: sqlx::PgPool) {
+    let lock = AdvisoryLock::acquire::<Forest>(&1, &pool).await.unwrap();
+    assert_eq!(lock.key(), advisory::key::<Forest>(&1));
+
+    assert!(AdvisoryLock::try_acquire::<Forest>(&1, &pool)
+        .await
+        .unwrap()
+        .is_none());
+
+    // other entities and tables are not affected
+    let other = AdvisoryLock::try_acquire::<Forest>(&2, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let tree = AdvisoryLock::try_acquire::<Tree>(&1, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+
+    lock.release().await.unwrap();
+    other.release().await.unwrap();
+    drop(tree);
+
+    AdvisoryLock::try_acquire::<Forest>(&1, &pool)
+        .await
+        .unwrap()
+        .unwrap()
+        .release()
+        .await
+        .unwrap();
+
+    // dropping the guard closes its session, which the server notices asynchronously
+    let mut released = false;
+
+    for _ in 0..50 {
+        if let Some(lock) = AdvisoryLock::try_acquire::<Tree>(&1, &pool).await.unwrap() {
+            lock.release().await.unwrap();
+            released = true;
+            break;
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    assert!(released);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn transaction(pool: sqlx::PgPool) {
+    let mut tx = Transaction::begin(&pool).await.unwrap();
+    let mut concurrent = Transaction::begin(&pool).await.unwrap();
+
+    advisory::lock_xact::<Forest, _>(&1, &mut tx).await.unwrap();
+
+    assert!(!advisory::try_lock_xact::<Forest, _>(&1, &mut concurrent)
+        .await
+        .unwrap());
+    assert!(advisory::try_lock_xact::<Forest, _>(&2, &mut concurrent)
+        .await
+        .unwrap());
+
+    tx.commit().await.unwrap();
+
+    assert!(advisory::try_lock_xact::<Forest, _>(&1, &mut concurrent)
+        .await
+        .unwrap());
+
+    concurrent.commit().await.unwrap();
+}
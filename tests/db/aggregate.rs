@@ -0,0 +1,170 @@
+use atmosphere::filter::Filter;
+use atmosphere::prelude::*;
+use atmosphere::Aggregation;
+
+use super::fixtures::{Document, Forest, Tree};
+
+async fn trees(pool: &sqlx::PgPool) {
+    for id in [1, 2] {
+        Forest {
+            id,
+            name: format!("forest {id}"),
+            location: "berlin".to_owned(),
+        }
+        .create(pool)
+        .await
+        .unwrap();
+    }
+
+    for id in 1..=6 {
+        let forest = if id <= 4 { 1 } else { 2 };
+        Tree { id, forest }.create(pool).await.unwrap();
+    }
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn aggregates(pool: sqlx::PgPool) {
+    trees(&pool).await;
+
+    let all = Filter::new();
+    let first = Filter::new().eq("forest", 1);
+
+    assert_eq!(Tree::count(&all, &pool).await.unwrap(), 6);
+    assert_eq!(Tree::count(&first, &pool).await.unwrap(), 4);
+
+    assert_eq!(
+        Tree::sum(Tree::col().id, &first, &pool).await.unwrap(),
+        Some(10)
+    );
+    assert_eq!(
+        Tree::avg(Tree::col().id, &first, &pool).await.unwrap(),
+        Some(2.5)
+    );
+    assert_eq!(
+        Tree::minimum(Tree::col().id, &all, &pool).await.unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        Tree::maximum(Tree::col().forest, &all, &pool)
+            .await
+            .unwrap(),
+        Some(2)
+    );
+
+    let name: Option<String> = Forest::maximum(Forest::col().name, &Filter::new(), &pool)
+        .await
+        .unwrap();
+    assert_eq!(name.as_deref(), Some("forest 2"));
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn empty(pool: sqlx::PgPool) {
+    let all = Filter::new();
+
+    assert_eq!(Document::count(&all, &pool).await.unwrap(), 0);
+    assert_eq!(
+        Document::sum(Document::col().version, &all, &pool)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        Document::avg(Document::col().version, &all, &pool)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        Document::maximum(Document::col().title, &all, &pool)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn widened(pool: sqlx::PgPool) {
+    for id in 0..3 {
+        Document {
+            id,
+            title: format!("document {id}"),
+            version: i64::from(i32::MAX),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    // postgres sums BIGINT columns as NUMERIC, which is cast back to BIGINT
+    assert_eq!(
+        Document::sum(Document::col().version, &Filter::new(), &pool)
+            .await
+            .unwrap(),
+        Some(3 * i64::from(i32::MAX))
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn group_by(pool: sqlx::PgPool) {
+    trees(&pool).await;
+
+    let trees: Vec<(i32, i64)> = Tree::group_by(
+        Tree::col().forest,
+        &Aggregation::count(),
+        &Filter::new(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(trees, [(1, 4), (2, 2)]);
+
+    let largest: Vec<(i32, Option<i32>)> = Tree::group_by(
+        Tree::col().forest,
+        &Aggregation::max(Tree::col().id),
+        &Filter::new().lt("id", 6),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(largest, [(1, Some(4)), (2, Some(5))]);
+
+    let forests: Vec<((String, String), Option<i64>)> = Forest::group_by(
+        (Forest::col().location, Forest::col().name),
+        &Aggregation::sum(Forest::col().id),
+        &Filter::new(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        forests,
+        [
+            (("berlin".to_owned(), "forest 1".to_owned()), Some(1)),
+            (("berlin".to_owned(), "forest 2".to_owned()), Some(2)),
+        ]
+    );
+}
+
+#[derive(Schema, Debug, PartialEq, Eq, Clone)]
+#[table(name = "audit", schema = "public")]
+struct Audit {
+    #[sql(pk)]
+    id: i32,
+    table: String,
+    schema: String,
+    primary_key: i32,
+}
+
+#[test]
+fn columns_named_like_table_constants() {
+    assert_eq!(Audit::TABLE, "audit");
+    assert_eq!(Audit::SCHEMA, "public");
+    assert_eq!(Audit::PRIMARY_KEY.field, "id");
+
+    assert_eq!(Audit::col().table.column().field(), "table");
+    assert_eq!(Audit::col().schema.column().field(), "schema");
+    assert_eq!(Audit::col().primary_key.column().field(), "primary_key");
+}
@@ -0,0 +1,74 @@
+use atmosphere::prelude::*;
+use atmosphere::runtime::sql;
+use atmosphere::transaction::Transaction;
+
+use super::fixtures::Forest;
+
+fn forests(ids: std::ops::Range<i32>) -> Vec<Forest> {
+    ids.map(|id| Forest {
+        id,
+        name: format!("forest {id}"),
+        location: "berlin".to_owned(),
+    })
+    .collect()
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn create_many(pool: sqlx::PgPool) {
+    assert_eq!(
+        Forest::create_many(&mut [], &pool)
+            .await
+            .unwrap()
+            .rows_affected(),
+        0
+    );
+
+    let mut created = forests(0..3);
+
+    assert_eq!(
+        Forest::create_many(&mut created, &pool)
+            .await
+            .unwrap()
+            .rows_affected(),
+        3
+    );
+
+    let mut found = Forest::find_all(&pool).await.unwrap();
+    found.sort();
+
+    assert_eq!(found, created);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn chunked(pool: sqlx::PgPool) {
+    let count = 2 * sql::insert_many_chunk::<Forest>() + 1;
+
+    let mut created = forests(0..count as i32);
+
+    let mut tx = Transaction::begin(&pool).await.unwrap();
+
+    assert_eq!(
+        Forest::create_many(&mut created, &mut tx)
+            .await
+            .unwrap()
+            .rows_affected(),
+        count as u64
+    );
+
+    tx.commit().await.unwrap();
+
+    assert_eq!(Forest::find_all(&pool).await.unwrap().len(), count);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn atomic(pool: sqlx::PgPool) {
+    let count = sql::insert_many_chunk::<Forest>() + 1;
+
+    // the duplicate is part of the second statement
+    let mut created = forests(0..count as i32);
+    created.push(created[0].clone());
+
+    assert!(Forest::create_many(&mut created, &pool).await.is_err());
+
+    assert!(Forest::find_all(&pool).await.unwrap().is_empty());
+}
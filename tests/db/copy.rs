@@ -0,0 +1,160 @@
+use atmosphere::copy::{self, CopyFormat};
+use atmosphere::prelude::*;
+use sqlx::types::chrono::DateTime;
+
+use super::fixtures::{Document, Forest, Ranger};
+
+#[test]
+fn statement() {
+    assert_eq!(
+        copy::statement::<Document>(CopyFormat::Csv),
+        "COPY \"public\".\"document\" (id, title, version) FROM STDIN WITH (FORMAT csv)"
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn copy_in(pool: sqlx::PgPool) {
+    let forests: Vec<Forest> = (0..10_000)
+        .map(|id| Forest {
+            id,
+            name: format!("forest {id}"),
+            location: "berlin, \"germany\"".to_owned(),
+        })
+        .collect();
+
+    assert_eq!(
+        copy::copy_in(&forests, CopyFormat::Binary, &pool)
+            .await
+            .unwrap(),
+        10_000
+    );
+
+    let mut found = Forest::find_all(&pool).await.unwrap();
+    found.sort();
+
+    assert_eq!(found, forests);
+
+    // a failing row fails the whole copy
+    assert!(copy::copy_in(&forests[..1], CopyFormat::Binary, &pool)
+        .await
+        .is_err());
+    assert_eq!(Forest::find_all(&pool).await.unwrap().len(), 10_000);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn copy_in_csv_entities(pool: sqlx::PgPool) {
+    let documents = vec![
+        Document {
+            id: 1,
+            title: String::new(),
+            version: 0,
+        },
+        Document {
+            id: 2,
+            title: "final, \"reviewed\"\nversion".to_owned(),
+            version: -3,
+        },
+    ];
+
+    assert_eq!(
+        copy::copy_in(&documents, CopyFormat::Csv, &pool)
+            .await
+            .unwrap(),
+        2
+    );
+
+    let mut found = Document::find_all(&pool).await.unwrap();
+    found.sort();
+
+    assert_eq!(found, documents);
+
+    let forest = Forest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    };
+
+    let ranger = Ranger {
+        id: 1,
+        forest: 1,
+        name: "jane".to_owned(),
+        created_at: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+    };
+
+    copy::copy_in([&forest], CopyFormat::Csv, &pool)
+        .await
+        .unwrap();
+    copy::copy_in([&ranger], CopyFormat::Csv, &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(Ranger::find(&1, &pool).await.unwrap(), ranger);
+}
+
+/// A table with a `"char"` column, which is created by the test
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "grade", schema = "public")]
+struct Grade {
+    #[sql(pk)]
+    id: i32,
+    letter: i8,
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn copy_in_csv_char(pool: sqlx::PgPool) {
+    sqlx::query("CREATE TABLE grade (id INTEGER PRIMARY KEY, letter \"char\" NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let grades = vec![
+        Grade {
+            id: 1,
+            letter: b'A' as i8,
+        },
+        Grade { id: 2, letter: 0 },
+        Grade {
+            id: 3,
+            letter: b'"' as i8,
+        },
+    ];
+
+    assert_eq!(
+        copy::copy_in(&grades, CopyFormat::Csv, &pool)
+            .await
+            .unwrap(),
+        3
+    );
+
+    let mut found = Grade::find_all(&pool).await.unwrap();
+    found.sort();
+
+    assert_eq!(found, grades);
+
+    let err = copy::copy_in([&Grade { id: 4, letter: -23 }], CopyFormat::Csv, &pool)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Bind(BindError::Unsupported(_))));
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn copy_in_csv(pool: sqlx::PgPool) {
+    let csv = ["1,draft,0\n2,\"final, re", "viewed\",3\n"];
+
+    assert_eq!(
+        copy::copy_in_csv::<Document, _, _>(csv, &pool)
+            .await
+            .unwrap(),
+        2
+    );
+
+    assert_eq!(
+        Document::find(&2, &pool).await.unwrap(),
+        Document {
+            id: 2,
+            title: "final, reviewed".to_owned(),
+            version: 3,
+        }
+    );
+}
@@ -1,5 +1,4 @@
 use atmosphere::prelude::*;
-use atmosphere_core::Table;
 
 #[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[table(name = "forest", schema = "public")]
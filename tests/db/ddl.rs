@@ -0,0 +1,99 @@
+use atmosphere::prelude::*;
+use sqlx::types::chrono::{DateTime, Utc};
+
+use super::fixtures::Forest;
+
+#[derive(Schema, Debug, PartialEq, Eq, Clone)]
+#[table(name = "cabin", schema = "public")]
+struct Cabin {
+    #[sql(pk, generated)]
+    id: i32,
+    #[sql(fk -> Forest, rename = "forest_id")]
+    forest: i32,
+    #[sql(unique)]
+    name: String,
+    beds: Option<i16>,
+    #[sql(version)]
+    version: i64,
+    #[sql(timestamp = created)]
+    built_at: DateTime<Utc>,
+}
+
+/// A column type without sql type, which must not prevent deriving `Schema`
+#[derive(sqlx::Type, Debug, PartialEq, Eq, Clone)]
+#[sqlx(transparent)]
+struct Coordinates(String);
+
+#[derive(Schema, Debug, PartialEq, Eq, Clone)]
+#[table(name = "forest", schema = "public")]
+struct Located {
+    #[sql(pk)]
+    id: i32,
+    #[sql(rename = "location")]
+    coordinates: Coordinates,
+}
+
+#[test]
+fn statements() {
+    assert_eq!(
+        Cabin::create_table_sql(),
+        "CREATE TABLE \"public\".\"cabin\" (\n  id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,\n  forest_id INTEGER NOT NULL REFERENCES \"public\".\"forest\"(id),\n  beds SMALLINT,\n  name TEXT NOT NULL UNIQUE,\n  version BIGINT NOT NULL,\n  built_at TIMESTAMPTZ NOT NULL\n)"
+    );
+
+    assert_eq!(Cabin::drop_table_sql(), "DROP TABLE \"public\".\"cabin\"");
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn create_and_drop(pool: sqlx::PgPool) {
+    sqlx::query(&Cabin::create_table_sql())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    Forest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    }
+    .create(&pool)
+    .await
+    .unwrap();
+
+    let mut cabin = Cabin {
+        id: 1,
+        forest: 1,
+        name: "hut".to_owned(),
+        beds: None,
+        version: 0,
+        built_at: DateTime::from_timestamp(0, 0).unwrap(),
+    };
+
+    cabin.create(&pool).await.unwrap();
+    assert_eq!(Cabin::find(&1, &pool).await.unwrap(), cabin);
+
+    // the name is unique
+    let mut duplicate = Cabin {
+        id: 2,
+        ..cabin.clone()
+    };
+    assert!(duplicate.create(&pool).await.is_err());
+
+    // the forest must exist
+    let mut orphan = Cabin {
+        id: 3,
+        forest: 2,
+        name: "shed".to_owned(),
+        ..cabin.clone()
+    };
+    assert!(orphan.create(&pool).await.is_err());
+
+    sqlx::query(&Cabin::drop_table_sql())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(Cabin::find(&1, &pool).await.is_err());
+
+    let located = Located::find(&1, &pool).await.unwrap();
+    assert_eq!(located.coordinates, Coordinates("berlin".to_owned()));
+}
@@ -0,0 +1,52 @@
+use atmosphere::prelude::*;
+use atmosphere::query::{Operation, QueryError, ViolationError};
+
+use super::fixtures::{Forest, Tree};
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn not_found(pool: sqlx::PgPool) {
+    let err = Forest::find(&0, &pool).await.unwrap_err();
+
+    let Error::Query(QueryError::NotFound(_, ctx)) = err else {
+        panic!("expected a not found error, got {err:?}");
+    };
+
+    assert_eq!(ctx.schema, Some("public"));
+    assert_eq!(ctx.table, Some("forest"));
+    assert_eq!(ctx.op, Some(Operation::Select));
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn violation(pool: sqlx::PgPool) {
+    let forest = Forest {
+        id: 0,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    };
+
+    forest.clone().create(&pool).await.unwrap();
+
+    let err = forest.clone().create(&pool).await.unwrap_err();
+
+    let Error::Query(QueryError::Violation(ViolationError::Unique(_, ctx))) = err else {
+        panic!("expected a uniqueness violation, got {err:?}");
+    };
+
+    assert_eq!(ctx.table, Some("forest"));
+    assert_eq!(ctx.op, Some(Operation::Insert));
+    assert_eq!(ctx.constraint.as_deref(), Some("forest_pkey"));
+    assert_eq!(ctx.column.as_deref(), Some("id"));
+    assert_eq!(ctx.field, Some("id"));
+
+    let err = Tree { id: 0, forest: 99 }.create(&pool).await.unwrap_err();
+
+    let Error::Query(QueryError::Violation(ViolationError::ForeignKey(_, ctx))) = err else {
+        panic!("expected a foreign key violation, got {err:?}");
+    };
+
+    assert_eq!(ctx.table, Some("tree"));
+    assert_eq!(ctx.op, Some(Operation::Insert));
+    assert_eq!(ctx.constraint.as_deref(), Some("tree_forest_id_fkey"));
+    assert_eq!(ctx.column.as_deref(), Some("forest_id"));
+    assert_eq!(ctx.field, Some("forest"));
+}
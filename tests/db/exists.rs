@@ -0,0 +1,42 @@
+use atmosphere::filter::Filter;
+use atmosphere::prelude::*;
+
+use super::fixtures::Species;
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn exists(pool: sqlx::PgPool) {
+    assert!(!Species::exists(&1, &pool).await.unwrap());
+    assert!(!Species::exists_where(&Filter::new(), &pool).await.unwrap());
+
+    Species {
+        id: 1,
+        name: "oak".to_owned(),
+        genus: "quercus".to_owned(),
+        description: "deciduous".to_owned(),
+    }
+    .create(&pool)
+    .await
+    .unwrap();
+
+    assert!(Species::exists(&1, &pool).await.unwrap());
+    assert!(!Species::exists(&2, &pool).await.unwrap());
+
+    assert!(Species::exists_where(&Filter::new(), &pool).await.unwrap());
+    assert!(
+        Species::exists_where(&Filter::new().eq("genus", "quercus"), &pool)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !Species::exists_where(&Filter::new().eq("genus", "fagus"), &pool)
+            .await
+            .unwrap()
+    );
+
+    assert!(Species::exists_by_name(&"oak".to_owned(), &pool)
+        .await
+        .unwrap());
+    assert!(!Species::exists_by_name(&"beech".to_owned(), &pool)
+        .await
+        .unwrap());
+}
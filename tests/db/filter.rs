@@ -0,0 +1,139 @@
+use atmosphere::filter::{Assignments, Filter};
+use atmosphere::prelude::*;
+
+use super::fixtures::{Document, Forest};
+
+async fn forests(pool: &sqlx::PgPool) {
+    for (id, location) in [(1, "berlin"), (2, "berlin"), (3, "munich")] {
+        Forest {
+            id,
+            name: format!("forest {id}"),
+            location: location.to_owned(),
+        }
+        .create(pool)
+        .await
+        .unwrap();
+    }
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn update_where(pool: sqlx::PgPool) {
+    forests(&pool).await;
+
+    let filter = Filter::new().eq("location", "berlin");
+    let assignments = Assignments::new().set("location", "brandenburg");
+
+    assert_eq!(
+        Forest::update_where(&filter, &assignments, &pool)
+            .await
+            .unwrap(),
+        2
+    );
+
+    let filter = Filter::new().eq("location", "brandenburg");
+
+    assert_eq!(
+        Forest::update_where(
+            &filter.or(Filter::new().eq("id", 3)),
+            &Assignments::new(),
+            &pool
+        )
+        .await
+        .unwrap(),
+        0
+    );
+
+    let mut locations: Vec<String> = Forest::find_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.location)
+        .collect();
+
+    locations.sort();
+
+    assert_eq!(locations, ["brandenburg", "brandenburg", "munich"]);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn delete_where(pool: sqlx::PgPool) {
+    forests(&pool).await;
+
+    let filter = Filter::new().eq("location", "berlin").ne("id", 1);
+
+    assert_eq!(Forest::delete_where(&filter, &pool).await.unwrap(), 1);
+
+    let filter = Filter::new().is_in("id", [1, 2, 3]);
+
+    assert_eq!(Forest::delete_where(&filter, &pool).await.unwrap(), 2);
+    assert!(Forest::find_all(&pool).await.unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn empty(pool: sqlx::PgPool) {
+    forests(&pool).await;
+
+    assert!(matches!(
+        Forest::delete_where(&Filter::new(), &pool).await,
+        Err(Error::EmptyFilter)
+    ));
+
+    let assignments = Assignments::new().set("name", "forest");
+
+    assert!(matches!(
+        Forest::update_where(&Filter::new(), &assignments, &pool).await,
+        Err(Error::EmptyFilter)
+    ));
+
+    // filters are validated even if nothing is assigned
+    assert!(matches!(
+        Forest::update_where(&Filter::new(), &Assignments::new(), &pool).await,
+        Err(Error::EmptyFilter)
+    ));
+
+    assert!(matches!(
+        Forest::update_where(&Filter::new().eq("unknown", 1), &Assignments::new(), &pool).await,
+        Err(Error::Bind(BindError::Unknown("unknown")))
+    ));
+
+    assert_eq!(
+        Forest::update_where(&Filter::all(), &assignments, &pool)
+            .await
+            .unwrap(),
+        3
+    );
+
+    assert_eq!(
+        Forest::delete_where(&Filter::all(), &pool).await.unwrap(),
+        3
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn versioned(pool: sqlx::PgPool) {
+    let mut document = Document {
+        id: 1,
+        title: "draft".to_owned(),
+        version: 0,
+    };
+
+    document.create(&pool).await.unwrap();
+
+    let filter = Filter::new().eq("title", "draft");
+    let assignments = Assignments::new().set("title", "final");
+
+    assert_eq!(
+        Document::update_where(&filter, &assignments, &pool)
+            .await
+            .unwrap(),
+        1
+    );
+
+    assert_eq!(Document::find(&1, &pool).await.unwrap().version, 1);
+
+    // the update is detected by holders of the previous version
+    assert!(matches!(
+        document.update(&pool).await,
+        Err(Error::StaleEntity)
+    ));
+}
@@ -0,0 +1,60 @@
+//! The schemas of the tables created by the migrations, shared by the tests.
+
+use atmosphere::prelude::*;
+use sqlx::types::chrono::{DateTime, Utc};
+
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "forest", schema = "public")]
+#[patch(derive(Debug, Clone))]
+#[insertable(derive(Debug, Clone))]
+pub struct Forest {
+    #[sql(pk)]
+    pub id: i32,
+    pub name: String,
+    pub location: String,
+}
+
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "tree", schema = "public")]
+pub struct Tree {
+    #[sql(pk)]
+    pub id: i32,
+    #[sql(fk -> Forest, rename = "forest_id")]
+    pub forest: i32,
+}
+
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "document", schema = "public")]
+#[patch]
+pub struct Document {
+    #[sql(pk)]
+    pub id: i32,
+    pub title: String,
+    #[sql(version)]
+    pub version: i64,
+}
+
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "species", schema = "public")]
+pub struct Species {
+    #[sql(pk)]
+    pub id: i32,
+    #[sql(unique, searchable)]
+    pub name: String,
+    pub genus: String,
+    #[sql(searchable)]
+    pub description: String,
+}
+
+#[derive(Schema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[table(name = "ranger", schema = "public")]
+#[insertable]
+pub struct Ranger {
+    #[sql(pk, generated)]
+    pub id: i32,
+    #[sql(fk -> Forest, rename = "forest_id")]
+    pub forest: i32,
+    pub name: String,
+    #[sql(timestamp = created)]
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,39 @@
+use atmosphere::prelude::*;
+
+use super::fixtures::{Forest, NewForest, NewRanger, Ranger};
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn insert(pool: sqlx::PgPool) {
+    let forest = NewForest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    }
+    .insert(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(forest, Forest::find(&1, &pool).await.unwrap());
+
+    let first = NewRanger {
+        forest: forest.id,
+        name: "jane".to_owned(),
+    }
+    .insert(&pool)
+    .await
+    .unwrap();
+
+    let second = NewRanger {
+        forest: forest.id,
+        name: "john".to_owned(),
+    }
+    .insert(&pool)
+    .await
+    .unwrap();
+
+    // the primary key and the timestamp are assigned by the database
+    assert_ne!(first.id, second.id);
+    assert!(first.created_at <= second.created_at);
+
+    assert_eq!(first, Ranger::find(&first.id, &pool).await.unwrap());
+}
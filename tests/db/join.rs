@@ -0,0 +1,128 @@
+use atmosphere::filter::Filter;
+use atmosphere::hooks::{Hook, HookInput, HookStage};
+use atmosphere::prelude::*;
+use atmosphere::query::Query;
+
+use super::fixtures::{Forest, Tree};
+
+async fn setup(pool: &sqlx::PgPool) -> Vec<Forest> {
+    let mut forests = vec![];
+
+    for id in 0..3 {
+        let forest = Forest {
+            id,
+            name: format!("forest {id}"),
+            location: "berlin".to_owned(),
+        };
+
+        forest.clone().create(pool).await.unwrap();
+        forests.push(forest);
+    }
+
+    // the last forest has no trees
+    for id in 0..4 {
+        Tree { id, forest: id % 2 }.create(pool).await.unwrap();
+    }
+
+    forests
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn inner_join(pool: sqlx::PgPool) {
+    let forests = setup(&pool).await;
+
+    let mut joined = Tree::join_forest(&Filter::new(), &pool).await.unwrap();
+    joined.sort();
+
+    assert_eq!(
+        joined,
+        (0..4)
+            .map(|id| (
+                Tree { id, forest: id % 2 },
+                forests[id as usize % 2].clone()
+            ))
+            .collect::<Vec<_>>()
+    );
+
+    let joined = Tree::join_forest(&Filter::new().eq("id", 3), &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        joined,
+        vec![(Tree { id: 3, forest: 1 }, forests[1].clone())]
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn left_join(pool: sqlx::PgPool) {
+    let forests = setup(&pool).await;
+
+    let mut joined = Forest::join_trees(&Filter::new(), &pool).await.unwrap();
+    joined.sort();
+
+    assert_eq!(
+        joined,
+        vec![
+            (forests[0].clone(), Some(Tree { id: 0, forest: 0 })),
+            (forests[0].clone(), Some(Tree { id: 2, forest: 0 })),
+            (forests[1].clone(), Some(Tree { id: 1, forest: 1 })),
+            (forests[1].clone(), Some(Tree { id: 3, forest: 1 })),
+            (forests[2].clone(), None),
+        ]
+    );
+
+    let joined = Forest::join_trees(&Filter::new().eq("id", 2), &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(joined, vec![(forests[2].clone(), None)]);
+}
+
+#[derive(Schema, Debug)]
+#[table(name = "forest", schema = "public")]
+#[hooks(Reject)]
+struct Grove {
+    #[sql(pk)]
+    id: i32,
+    name: String,
+    location: String,
+}
+
+#[derive(Schema, Debug)]
+#[table(name = "tree", schema = "public")]
+#[hooks(Reject)]
+struct Sapling {
+    #[sql(pk)]
+    id: i32,
+    #[sql(fk -> Grove, rename = "forest_id")]
+    forest: i32,
+}
+
+struct Reject;
+
+#[async_trait]
+impl<T: Table + Bind + Sync> Hook<T> for Reject {
+    fn stage(&self) -> HookStage {
+        HookStage::PreExec
+    }
+
+    async fn apply(&self, _: &Query<T>, _: &mut HookInput<'_, T>) -> Result<()> {
+        Err(Error::Other)
+    }
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn join_hooks(pool: sqlx::PgPool) {
+    setup(&pool).await;
+
+    assert!(matches!(
+        Sapling::join_forest(&Filter::new(), &pool).await,
+        Err(Error::Other)
+    ));
+
+    assert!(matches!(
+        Grove::join_saplings(&Filter::new(), &pool).await,
+        Err(Error::Other)
+    ));
+}
@@ -0,0 +1,50 @@
+use atmosphere::prelude::*;
+use atmosphere::transaction::Transaction;
+
+use super::fixtures::Forest;
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn skip_locked(pool: sqlx::PgPool) {
+    for id in 0..3 {
+        Forest {
+            id,
+            name: "grunewald".to_owned(),
+            location: "berlin".to_owned(),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    let mut tx = Transaction::begin(&pool).await.unwrap();
+    let mut concurrent = Transaction::begin(&pool).await.unwrap();
+
+    let locked = Forest::find_for_update(&0, &mut tx).await.unwrap();
+    assert_eq!(locked.id, 0);
+
+    assert!(Forest::find_for_update_skip_locked(&0, &mut concurrent)
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut claimed = Forest::find_all_for_update_skip_locked(&mut concurrent)
+        .await
+        .unwrap();
+    claimed.sort();
+
+    assert_eq!(claimed.iter().map(|f| f.id).collect::<Vec<_>>(), vec![1, 2]);
+
+    // rows locked by the own transaction are not skipped
+    assert_eq!(
+        Forest::find_all_for_update_skip_locked(&mut tx)
+            .await
+            .unwrap(),
+        vec![locked]
+    );
+
+    concurrent.commit().await.unwrap();
+
+    assert_eq!(Forest::find_all_for_update(&mut tx).await.unwrap().len(), 3);
+
+    tx.commit().await.unwrap();
+}
@@ -0,0 +1,5 @@
+CREATE TABLE document (
+    id      INT PRIMARY KEY,
+    title   TEXT NOT NULL,
+    version BIGINT NOT NULL
+);
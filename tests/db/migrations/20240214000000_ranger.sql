@@ -0,0 +1,6 @@
+CREATE TABLE ranger (
+    id         SERIAL PRIMARY KEY,
+    forest_id  INT NOT NULL REFERENCES forest(id) ON DELETE CASCADE,
+    name       TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,6 @@
+CREATE TABLE species (
+    id          INT PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    genus       TEXT NOT NULL,
+    description TEXT NOT NULL
+);
@@ -1 +1,23 @@
+mod advisory;
+mod aggregate;
+mod bulk;
+mod copy;
 mod crud;
+mod ddl;
+mod errors;
+mod exists;
+mod filter;
+mod fixtures;
+mod insertable;
+mod join;
+mod lock;
+mod patch;
+mod projection;
+mod raw;
+mod search;
+mod statements;
+mod stream;
+mod tracked;
+mod transaction;
+mod upsert;
+mod version;
@@ -0,0 +1,77 @@
+use atmosphere::prelude::*;
+
+use super::fixtures::{Document, DocumentPatch, Forest, ForestPatch};
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn apply(pool: sqlx::PgPool) {
+    Forest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    }
+    .create(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(
+        Forest::apply_patch(&1, &ForestPatch::default(), &pool)
+            .await
+            .unwrap()
+            .rows_affected(),
+        0
+    );
+
+    let patch = ForestPatch {
+        location: Some("brandenburg".to_owned()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        Forest::apply_patch(&1, &patch, &pool)
+            .await
+            .unwrap()
+            .rows_affected(),
+        1
+    );
+
+    assert_eq!(
+        Forest::find(&1, &pool).await.unwrap(),
+        Forest {
+            id: 1,
+            name: "grunewald".to_owned(),
+            location: "brandenburg".to_owned(),
+        }
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn versioned(pool: sqlx::PgPool) {
+    let mut document = Document {
+        id: 1,
+        title: "draft".to_owned(),
+        version: 0,
+    };
+
+    document.create(&pool).await.unwrap();
+
+    let patch = DocumentPatch {
+        title: Some("final".to_owned()),
+    };
+
+    Document::apply_patch(&1, &patch, &pool).await.unwrap();
+
+    assert_eq!(
+        Document::find(&1, &pool).await.unwrap(),
+        Document {
+            id: 1,
+            title: "final".to_owned(),
+            version: 1,
+        }
+    );
+
+    // the patch is detected by holders of the previous version
+    assert!(matches!(
+        document.update(&pool).await,
+        Err(Error::StaleEntity)
+    ));
+}
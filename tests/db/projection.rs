@@ -0,0 +1,48 @@
+use atmosphere::filter::Filter;
+use atmosphere::prelude::*;
+
+use super::fixtures::Species;
+
+#[derive(Projection, Debug, PartialEq, Eq)]
+#[projection(of = Species)]
+struct SpeciesName {
+    name: String,
+    id: i32,
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn projection(pool: sqlx::PgPool) {
+    for (id, name, genus) in [(1, "oak", "quercus"), (2, "beech", "fagus")] {
+        Species {
+            id,
+            name: name.to_owned(),
+            genus: genus.to_owned(),
+            description: "a very long description".repeat(100),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        SpeciesName::find(&1, &pool).await.unwrap(),
+        SpeciesName {
+            name: "oak".to_owned(),
+            id: 1,
+        }
+    );
+
+    assert!(SpeciesName::find(&3, &pool).await.is_err());
+
+    let names = SpeciesName::find_where(&Filter::new().eq("genus", "fagus"), &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        names,
+        [SpeciesName {
+            name: "beech".to_owned(),
+            id: 2,
+        }]
+    );
+}
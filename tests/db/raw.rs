@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use atmosphere::hooks::{Hook, HookInput, HookStage};
+use atmosphere::prelude::*;
+use atmosphere::query::{Operation, Query, QueryError};
+
+/// Unlike the shared fixture, the forest renames a column and counts executed queries
+#[derive(Schema, Debug, PartialEq, Eq, Clone)]
+#[table(name = "forest", schema = "public")]
+#[hooks(Counter)]
+struct Forest {
+    #[sql(pk)]
+    id: i32,
+    name: String,
+    #[sql(rename = "location")]
+    place: String,
+}
+
+static EXECUTED: AtomicUsize = AtomicUsize::new(0);
+
+struct Counter;
+
+#[async_trait]
+impl Hook<Forest> for Counter {
+    fn stage(&self) -> HookStage {
+        HookStage::PostExec
+    }
+
+    async fn apply(&self, _: &Query<Forest>, _: &mut HookInput<'_, Forest>) -> Result<()> {
+        EXECUTED.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn query_raw(pool: sqlx::PgPool) {
+    for (id, place) in [(1, "berlin"), (2, "munich"), (3, "berlin")] {
+        Forest {
+            id,
+            name: format!("forest {id}"),
+            place: place.to_owned(),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    let executed = EXECUTED.load(Ordering::SeqCst);
+
+    let forests = Forest::query_raw("SELECT * FROM {table} WHERE {place} = $1 ORDER BY {id} DESC")
+        .bind("berlin")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(forests.iter().map(|f| f.id).collect::<Vec<_>>(), vec![3, 1]);
+
+    let forest = Forest::query_raw("SELECT * FROM {table} WHERE {id} = $1")
+        .bind(2)
+        .fetch_optional(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(forest.map(|f| f.place), Some("munich".to_owned()));
+
+    let result = Forest::query_raw("DELETE FROM {table} WHERE {place} = $1")
+        .bind("berlin")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(result.rows_affected(), 2);
+
+    assert_eq!(EXECUTED.load(Ordering::SeqCst), executed + 3);
+
+    let err = Forest::query_raw("SELECT * FROM {table} WHERE {id} = $1")
+        .bind(1)
+        .fetch_one(&pool)
+        .await
+        .unwrap_err();
+
+    let Error::Query(QueryError::NotFound(_, ctx)) = err else {
+        panic!("expected a not found error, got {err:?}");
+    };
+
+    assert_eq!(ctx.table, Some("forest"));
+    assert_eq!(ctx.op, Some(Operation::Select));
+
+    let err = Forest::query_raw("SELECT * FROM {table} WHERE {location} = $1")
+        .bind("berlin")
+        .fetch_all(&pool)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Bind(BindError::Identifier(_))));
+}
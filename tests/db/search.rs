@@ -0,0 +1,117 @@
+use atmosphere::prelude::*;
+use atmosphere::runtime::sql;
+
+use super::fixtures::Species;
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn search(pool: sqlx::PgPool) {
+    for (id, name, genus, description) in [
+        (1, "oak", "quercus", "a deciduous tree with lobed leaves"),
+        (
+            2,
+            "beech",
+            "fagus",
+            "a deciduous tree with smooth bark, related to the oak",
+        ),
+        (3, "pine", "pinus", "an evergreen conifer with needles"),
+    ] {
+        Species {
+            id,
+            name: name.to_owned(),
+            genus: genus.to_owned(),
+            description: description.to_owned(),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    let ids = |species: Vec<Species>| species.into_iter().map(|s| s.id).collect::<Vec<_>>();
+
+    // the name and the description match, which ranks the oak first
+    assert_eq!(
+        ids(Species::search("oak", &pool).await.unwrap()),
+        vec![1, 2]
+    );
+
+    let mut deciduous = ids(Species::search("deciduous trees", &pool).await.unwrap());
+    deciduous.sort();
+
+    assert_eq!(deciduous, vec![1, 2]);
+
+    assert_eq!(
+        ids(Species::search("deciduous -oak", &pool).await.unwrap()),
+        Vec::<i32>::new()
+    );
+
+    assert_eq!(
+        ids(Species::search("needles", &pool).await.unwrap()),
+        vec![3]
+    );
+
+    // the genus is not searchable
+    assert!(Species::search("quercus", &pool).await.unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn search_index(pool: sqlx::PgPool) {
+    // the expression index recommended by `Search`, over the searchable columns in field order
+    sqlx::query(
+        "CREATE INDEX species_search ON species USING GIN \
+         (to_tsvector('english', coalesce(description, '') || ' ' || coalesce(name, '')))",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let search = sql::search::<Species>(Species::SEARCH_COLUMNS, Species::CONFIG);
+
+    let mut conn = pool.acquire().await.unwrap();
+
+    sqlx::query("SET enable_seqscan = off")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    let plan: Vec<String> = sqlx::query_scalar(&format!("EXPLAIN {}", search.sql()))
+        .bind("oak")
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+
+    assert!(
+        plan.iter().any(|line| line.contains("species_search")),
+        "expected the search to use the index, got {plan:#?}"
+    );
+}
+
+/// Searches the species without stemming
+#[derive(Schema, Debug, PartialEq, Eq, Clone)]
+#[table(name = "species", schema = "public", search_config = "simple")]
+struct Unstemmed {
+    #[sql(pk)]
+    id: i32,
+    #[sql(searchable)]
+    description: String,
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn search_config(pool: sqlx::PgPool) {
+    Species {
+        id: 1,
+        name: "pine".to_owned(),
+        genus: "pinus".to_owned(),
+        description: "an evergreen conifer with needles".to_owned(),
+    }
+    .create(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(Species::CONFIG, "english");
+    assert_eq!(Unstemmed::CONFIG, "simple");
+
+    // `english` stems `needle` to match `needles`, `simple` does not
+    assert_eq!(Species::search("needle", &pool).await.unwrap().len(), 1);
+    assert!(Unstemmed::search("needle", &pool).await.unwrap().is_empty());
+    assert_eq!(Unstemmed::search("needles", &pool).await.unwrap().len(), 1);
+}
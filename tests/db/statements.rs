@@ -0,0 +1,51 @@
+use atmosphere::prelude::*;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::Connection;
+
+use super::fixtures::Species;
+
+async fn roundtrip(pool: &sqlx::PgPool) -> usize {
+    let mut species = Species {
+        id: 1,
+        name: "oak".to_owned(),
+        genus: "quercus".to_owned(),
+        description: "deciduous".to_owned(),
+    };
+
+    for _ in 0..2 {
+        species.save(pool).await.unwrap();
+        assert_eq!(Species::find(&1, pool).await.unwrap(), species);
+        assert_eq!(
+            Species::find_by_name(&"oak".to_owned(), pool)
+                .await
+                .unwrap(),
+            Some(species.clone())
+        );
+    }
+
+    species.delete(pool).await.unwrap();
+
+    pool.acquire().await.unwrap().cached_statements_size()
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn cached(options: PgPoolOptions, connect: PgConnectOptions) {
+    let pool = options
+        .max_connections(1)
+        .connect_with(connect)
+        .await
+        .unwrap();
+
+    assert!(roundtrip(&pool).await > 0);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn uncached(options: PgPoolOptions, connect: PgConnectOptions) {
+    let pool = options
+        .max_connections(1)
+        .connect_with(atmosphere::driver::without_statement_cache(connect))
+        .await
+        .unwrap();
+
+    assert_eq!(roundtrip(&pool).await, 0);
+}
@@ -0,0 +1,60 @@
+use std::future::poll_fn;
+
+use atmosphere::prelude::*;
+use atmosphere::rel::ReferredBy;
+
+use super::fixtures::{Forest, Tree};
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn stream_all(pool: sqlx::PgPool) {
+    for id in 0..10 {
+        Forest {
+            id,
+            name: format!("forest {id}"),
+            location: "berlin".to_owned(),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+    }
+
+    let mut stream = Forest::stream_all(&pool);
+    let mut ids = vec![];
+
+    while let Some(forest) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        ids.push(forest.unwrap().id);
+    }
+
+    ids.sort();
+
+    assert_eq!(ids, (0..10).collect::<Vec<_>>());
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn resolve_stream(pool: sqlx::PgPool) {
+    let forest = Forest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    };
+
+    forest.clone().create(&pool).await.unwrap();
+
+    for id in 0..5 {
+        Tree { id, forest: 1 }.create(&pool).await.unwrap();
+    }
+
+    let mut stream = <Forest as ReferredBy<Tree>>::resolve_stream(&forest, &pool);
+    let mut trees = vec![];
+
+    while let Some(tree) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        trees.push(tree.unwrap());
+    }
+
+    let mut resolved = forest.trees(&pool).await.unwrap();
+
+    trees.sort();
+    resolved.sort();
+
+    assert_eq!(trees, resolved);
+}
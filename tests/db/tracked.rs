@@ -0,0 +1,59 @@
+use atmosphere::prelude::*;
+
+use super::fixtures::Forest;
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn update(pool: sqlx::PgPool) {
+    Forest {
+        id: 1,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    }
+    .create(&pool)
+    .await
+    .unwrap();
+
+    let mut a = Tracked::new(Forest::find(&1, &pool).await.unwrap());
+    let mut b = Tracked::new(Forest::find(&1, &pool).await.unwrap());
+
+    assert!(!a.is_changed());
+    assert_eq!(a.update(&pool).await.unwrap().rows_affected(), 0);
+
+    // updating no columns is a no-op instead of an invalid statement
+    let mut entity = Forest::find(&1, &pool).await.unwrap();
+    assert_eq!(
+        entity
+            .update_columns(&[], &pool)
+            .await
+            .unwrap()
+            .rows_affected(),
+        0
+    );
+
+    a.name = "spandauer forst".to_owned();
+    b.location = "brandenburg".to_owned();
+
+    assert_eq!(
+        a.changes(),
+        vec![Column::Data(
+            &Forest::DATA_COLUMNS[Forest::DATA_COLUMNS
+                .iter()
+                .position(|c| c.field == "name")
+                .unwrap()]
+        )]
+    );
+
+    assert_eq!(a.update(&pool).await.unwrap().rows_affected(), 1);
+    assert_eq!(b.update(&pool).await.unwrap().rows_affected(), 1);
+    assert!(!a.is_changed());
+
+    // neither update overwrote the other
+    assert_eq!(
+        Forest::find(&1, &pool).await.unwrap(),
+        Forest {
+            id: 1,
+            name: "spandauer forst".to_owned(),
+            location: "brandenburg".to_owned(),
+        }
+    );
+}
@@ -0,0 +1,197 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use atmosphere::prelude::*;
+use atmosphere::transaction::{transaction_with_retry, RetryPolicy, Transaction};
+
+use super::fixtures::{Forest, Tree};
+
+fn forest(id: i32) -> Forest {
+    Forest {
+        id,
+        name: "grunewald".to_owned(),
+        location: "berlin".to_owned(),
+    }
+}
+
+const SERIALIZATION_FAILURE: &str =
+    "DO $$ BEGIN RAISE EXCEPTION 'conflict' USING ERRCODE = 'serialization_failure'; END $$";
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::new(3).with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn retry(pool: sqlx::PgPool) {
+    let attempts = AtomicU32::new(0);
+
+    transaction_with_retry(&pool, policy(), |tx| {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+
+        Box::pin(async move {
+            forest(0).create(&mut *tx).await?;
+
+            if attempt == 0 {
+                sqlx::query(SERIALIZATION_FAILURE)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(atmosphere::query::QueryError::from)?;
+            }
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(Forest::find_all(&pool).await.unwrap().len(), 1);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn exhausted(pool: sqlx::PgPool) {
+    let attempts = AtomicU32::new(0);
+
+    let err = transaction_with_retry(&pool, policy(), |tx| {
+        attempts.fetch_add(1, Ordering::SeqCst);
+
+        Box::pin(async move {
+            sqlx::query(SERIALIZATION_FAILURE)
+                .execute(&mut *tx)
+                .await
+                .map_err(atmosphere::query::QueryError::from)?;
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap_err();
+
+    assert!(err.is_retryable());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn not_retryable(pool: sqlx::PgPool) {
+    let attempts = AtomicU32::new(0);
+
+    let err = transaction_with_retry(&pool, policy(), |tx| {
+        attempts.fetch_add(1, Ordering::SeqCst);
+
+        Box::pin(async move {
+            forest(0).create(&mut *tx).await?;
+
+            Forest::find(&1, &mut *tx).await?;
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap_err();
+
+    assert!(!err.is_retryable());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    assert!(Forest::find_all(&pool).await.unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn commit(pool: sqlx::PgPool) {
+    let committed = Arc::new(AtomicBool::new(false));
+    let rolled_back = Arc::new(AtomicBool::new(false));
+
+    let mut tx = Transaction::begin(&pool).await.unwrap();
+
+    forest(0).create(&mut tx).await.unwrap();
+    Tree { id: 0, forest: 0 }.create(&mut tx).await.unwrap();
+
+    let forest = Forest::find(&0, &mut tx).await.unwrap();
+    assert_eq!(forest.trees(&mut tx).await.unwrap().len(), 1);
+
+    let flag = committed.clone();
+    tx.on_commit(move || flag.store(true, Ordering::SeqCst));
+    let flag = rolled_back.clone();
+    tx.on_rollback(move || flag.store(true, Ordering::SeqCst));
+
+    assert!(Forest::find_optional(&0, &pool).await.unwrap().is_none());
+
+    tx.commit().await.unwrap();
+
+    assert!(committed.load(Ordering::SeqCst));
+    assert!(!rolled_back.load(Ordering::SeqCst));
+    assert_eq!(Forest::find(&0, &pool).await.unwrap(), forest);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn rollback_on_drop(pool: sqlx::PgPool) {
+    let rolled_back = Arc::new(AtomicBool::new(false));
+
+    {
+        let mut tx = Transaction::begin(&pool).await.unwrap();
+
+        forest(0).create(&mut tx).await.unwrap();
+
+        let flag = rolled_back.clone();
+        tx.on_rollback(move || flag.store(true, Ordering::SeqCst));
+    }
+
+    assert!(rolled_back.load(Ordering::SeqCst));
+    assert!(Forest::find_optional(&0, &pool).await.unwrap().is_none());
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn savepoint(pool: sqlx::PgPool) {
+    let committed = Arc::new(AtomicU32::new(0));
+    let rolled_back = Arc::new(AtomicU32::new(0));
+
+    let mut tx = Transaction::begin(&pool).await.unwrap();
+
+    forest(0).create(&mut tx).await.unwrap();
+
+    let (c, r) = (committed.clone(), rolled_back.clone());
+
+    tx.savepoint(|sp| {
+        Box::pin(async move {
+            assert_eq!(sp.depth(), 1);
+
+            Tree { id: 0, forest: 0 }.create(&mut *sp).await?;
+            sp.on_commit(move || {
+                c.fetch_add(1, Ordering::SeqCst);
+            });
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    tx.savepoint(|sp| {
+        Box::pin(async move {
+            Tree { id: 1, forest: 0 }.create(&mut *sp).await?;
+            sp.on_rollback(move || {
+                r.fetch_add(1, Ordering::SeqCst);
+            });
+
+            sp.savepoint(|nested| {
+                Box::pin(async move {
+                    assert_eq!(nested.depth(), 2);
+                    Tree { id: 2, forest: 99 }.create(&mut *nested).await?;
+                    Ok(())
+                })
+            })
+            .await
+        })
+    })
+    .await
+    .expect_err("nested savepoint violated a foreign key");
+
+    assert_eq!(rolled_back.load(Ordering::SeqCst), 1);
+
+    tx.commit().await.unwrap();
+
+    assert_eq!(committed.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        Tree::find_all(&pool).await.unwrap(),
+        vec![Tree { id: 0, forest: 0 }]
+    );
+}
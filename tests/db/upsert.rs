@@ -0,0 +1,129 @@
+use atmosphere::prelude::*;
+
+use super::fixtures::{Document, Species};
+
+fn species(id: i32, name: &str, description: &str) -> Species {
+    Species {
+        id,
+        name: name.to_owned(),
+        genus: "quercus".to_owned(),
+        description: description.to_owned(),
+    }
+}
+
+async fn all(pool: &sqlx::PgPool) -> Vec<Species> {
+    let mut all = Species::find_all(pool).await.unwrap();
+    all.sort();
+    all
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn save_many(pool: sqlx::PgPool) {
+    let mut saved = vec![species(1, "oak", "old"), species(2, "beech", "old")];
+
+    Species::save_many(&mut saved, &Upsert::default(), &pool)
+        .await
+        .unwrap();
+
+    let mut saved = vec![species(2, "beech", "new"), species(3, "birch", "new")];
+
+    Species::save_many(&mut saved, &Upsert::default(), &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        all(&pool).await,
+        vec![
+            species(1, "oak", "old"),
+            species(2, "beech", "new"),
+            species(3, "birch", "new"),
+        ]
+    );
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn unique_target(pool: sqlx::PgPool) {
+    let mut saved = vec![species(1, "oak", "old")];
+
+    Species::create_many(&mut saved, &pool).await.unwrap();
+
+    // conflicts on the name, while the primary key differs
+    let mut conflicting = species(7, "oak", "new");
+    conflicting.genus = "fagus".to_owned();
+
+    Species::save_many(
+        &mut [conflicting],
+        &Species::on_conflict_name().update(["description"]),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(all(&pool).await, vec![species(1, "oak", "new")]);
+
+    Species::save_many(
+        &mut [species(7, "oak", "newer")],
+        &Species::on_conflict_name().keep(["description"]),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(all(&pool).await, vec![species(1, "oak", "new")]);
+
+    let res = Species::save_many(
+        &mut [species(7, "oak", "newest"), species(8, "ash", "new")],
+        &Species::on_conflict_name().do_nothing(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(res.rows_affected(), 1);
+
+    assert_eq!(
+        all(&pool).await,
+        vec![species(1, "oak", "new"), species(8, "ash", "new")]
+    );
+
+    assert!(matches!(
+        Species::save_many(
+            &mut [species(9, "elm", "new")],
+            &Upsert::default().update(["unknown"]),
+            &pool
+        )
+        .await,
+        Err(Error::Bind(_))
+    ));
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn versioned(pool: sqlx::PgPool) {
+    let mut documents: Vec<Document> = (0..2)
+        .map(|id| Document {
+            id,
+            title: "draft".to_owned(),
+            version: 0,
+        })
+        .collect();
+
+    Document::save_many(&mut documents, &Upsert::default(), &pool)
+        .await
+        .unwrap();
+
+    assert!(documents.iter().all(|d| d.version == 1));
+
+    let mut stale = documents.clone();
+    stale[1].version = 0;
+
+    assert!(matches!(
+        Document::save_many(&mut stale, &Upsert::default(), &pool).await,
+        Err(Error::StaleEntity)
+    ));
+
+    Document::save_many(&mut documents, &Upsert::default(), &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(Document::find(&0, &pool).await.unwrap().version, 2);
+}
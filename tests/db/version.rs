@@ -0,0 +1,55 @@
+use atmosphere::prelude::*;
+
+use super::fixtures::Document;
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn update(pool: sqlx::PgPool) {
+    let mut document = Document {
+        id: 0,
+        title: "draft".to_owned(),
+        version: 0,
+    };
+
+    document.create(&pool).await.unwrap();
+
+    let mut concurrent = Document::find(&0, &pool).await.unwrap();
+
+    document.title = "final".to_owned();
+    document.update(&pool).await.unwrap();
+    assert_eq!(document.version, 1);
+    assert_eq!(Document::find(&0, &pool).await.unwrap(), document);
+
+    concurrent.title = "overwritten".to_owned();
+    let err = concurrent.update(&pool).await.unwrap_err();
+    assert!(matches!(err, Error::StaleEntity));
+    assert_eq!(concurrent.version, 0);
+    assert_eq!(Document::find(&0, &pool).await.unwrap(), document);
+
+    concurrent.reload(&pool).await.unwrap();
+    concurrent.title = "revised".to_owned();
+    concurrent.update(&pool).await.unwrap();
+    assert_eq!(concurrent.version, 2);
+}
+
+#[sqlx::test(migrations = "tests/db/migrations")]
+async fn save(pool: sqlx::PgPool) {
+    let mut document = Document {
+        id: 0,
+        title: "draft".to_owned(),
+        version: 0,
+    };
+
+    document.save(&pool).await.unwrap();
+    assert_eq!(document.version, 1);
+
+    let mut concurrent = Document::find(&0, &pool).await.unwrap();
+    assert_eq!(concurrent, document);
+
+    document.title = "final".to_owned();
+    document.save(&pool).await.unwrap();
+    assert_eq!(document.version, 2);
+
+    let err = concurrent.save(&pool).await.unwrap_err();
+    assert!(matches!(err, Error::StaleEntity));
+    assert_eq!(Document::find(&0, &pool).await.unwrap(), document);
+}